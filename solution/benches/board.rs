@@ -0,0 +1,173 @@
+// Compares the bitboard Board with the previous representation (a HashMap of nodes with string keys and recursive mill checks).
+// Run with `cargo bench`.
+
+use solution::{Board, Color};
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+// the previous Board, reduced to the operations being measured
+mod legacy {
+    use solution::{Color, State};
+    use solution::enums::Direction;
+    use solution::node::Node;
+    use solution::Layout;
+
+    use std::collections::HashMap;
+
+    pub struct Board {
+        nodes: HashMap<&'static str, (State, Node)>
+    }
+
+    impl Board {
+        pub fn new() -> Self {
+            let layout = Layout::nine_mens_morris();
+            let nodes = (0..layout.len()).map(|index| (layout.name(index), (State::Empty, *layout.node(index)))).collect();
+
+            Self { nodes }
+        }
+
+        pub fn place_piece(&mut self, color: Color, position: &str) {
+            if let Some((state, _)) = self.nodes.get_mut(position) {
+                if *state == State::Empty {
+                    *state = State::Occupied(color);
+                }
+            }
+        }
+        pub fn remove_piece(&mut self, position: &str) {
+            if let Some((state, _)) = self.nodes.get_mut(position) {
+                *state = State::Empty;
+            }
+        }
+
+        pub fn can_move(&self, color: Color, position: &str) -> bool {
+            if let Some((state, node)) = self.nodes.get(position) {
+                if *state == State::Occupied(color) {
+                    return node.adjacent().iter().any(|neighbour| self.nodes[neighbour].0 == State::Empty);
+                }
+            }
+            false
+        }
+
+        pub fn in_mill(&self, color: Color, position: &str) -> bool {
+            match self.nodes.get(position) {
+                Some((state, node)) if *state == State::Occupied(color) => self.middle_of_mill(color, node) || self.edge_of_mill(color, node),
+                _ => false
+            }
+        }
+        fn middle_of_mill(&self, color: Color, node: &Node) -> bool {
+            let occupied = |neighbour: Option<&str>| neighbour.is_some_and(|neighbour| self.nodes[neighbour].0 == State::Occupied(color));
+
+            (node.left.is_some() && node.right.is_some() && occupied(node.left) && occupied(node.right)) ||
+            (node.above.is_some() && node.below.is_some() && occupied(node.above) && occupied(node.below))
+        }
+        fn edge_of_mill(&self, color: Color, node: &Node) -> bool {
+            [Direction::Above, Direction::Left, Direction::Right, Direction::Below].iter().any(|direction| self.check_direction(color, node, *direction, 1))
+        }
+        fn check_direction(&self, color: Color, node: &Node, direction: Direction, found: u8) -> bool {
+            if found == 3 {
+                return true;
+            }
+            match node.get_neighbour(direction) {
+                Some(neighbour) => {
+                    let (state, neighbour_node) = &self.nodes[neighbour];
+                    *state == State::Occupied(color) && self.check_direction(color, neighbour_node, direction, found + 1)
+                }
+                None => false
+            }
+        }
+    }
+}
+
+const ROUNDS: usize = 20_000;
+
+// a fixed sequence of placements by alternating colors
+const PLACEMENTS: [&str; 18] = ["a7", "d7", "g7", "d6", "b6", "d1",
+                                "f6", "a4", "c5", "g4", "e5", "b4",
+                                "a1", "f4", "g1", "c3", "d2", "e3"];
+
+// Fills the board one piece at a time, and after every placement checks mills and mobility for all positions.
+fn run_bitboard(positions: &[&'static str]) -> usize {
+    let mut found = 0;
+    for _ in 0..ROUNDS {
+        let mut board = Board::new();
+        for (i, placement) in PLACEMENTS.iter().enumerate() {
+            let color = if i % 2 == 0 { Color::White } else { Color::Black };
+            board.place_piece(color, placement).unwrap();
+
+            for position in positions {
+                found += board.in_mill(color, position) as usize + board.can_move(color, position) as usize;
+            }
+        }
+        for placement in PLACEMENTS.iter().step_by(2) {
+            board.remove_piece(Color::White, placement, false).unwrap();
+        }
+        black_box(&board);
+    }
+    found
+}
+
+// the same, using the index-based functions which skip looking up the positions by name
+fn run_bitboard_indices(positions: &[&'static str]) -> usize {
+    let mut found = 0;
+    for _ in 0..ROUNDS {
+        let mut board = Board::new();
+        for (i, placement) in PLACEMENTS.iter().enumerate() {
+            let color = if i % 2 == 0 { Color::White } else { Color::Black };
+            board.place_piece(color, placement).unwrap();
+
+            let empty = board.empty();
+            for index in 0..positions.len() {
+                let mobile = board.occupied(color) & 1 << index != 0 && board.layout().adjacent(index) & empty != 0;
+                found += board.in_mill_at(color, index) as usize + mobile as usize;
+            }
+        }
+        for placement in PLACEMENTS.iter().step_by(2) {
+            board.remove_piece(Color::White, placement, false).unwrap();
+        }
+        black_box(&board);
+    }
+    found
+}
+
+fn run_legacy(positions: &[&'static str]) -> usize {
+    let mut found = 0;
+    for _ in 0..ROUNDS {
+        let mut board = legacy::Board::new();
+        for (i, placement) in PLACEMENTS.iter().enumerate() {
+            let color = if i % 2 == 0 { Color::White } else { Color::Black };
+            board.place_piece(color, placement);
+
+            for position in positions {
+                found += board.in_mill(color, position) as usize + board.can_move(color, position) as usize;
+            }
+        }
+        for placement in PLACEMENTS.iter().step_by(2) {
+            board.remove_piece(placement);
+        }
+        black_box(&board);
+    }
+    found
+}
+
+fn time<F: FnMut() -> usize>(mut f: F) -> (Duration, usize) {
+    let start = Instant::now();
+    let result = black_box(f());
+
+    (start.elapsed(), result)
+}
+
+fn main() {
+    let positions = Board::new().positions();
+
+    let (legacy, legacy_found) = time(|| run_legacy(&positions));
+    let (bitboard, bitboard_found) = time(|| run_bitboard(&positions));
+    let (indices, indices_found) = time(|| run_bitboard_indices(&positions));
+    assert_eq!(bitboard_found, legacy_found, "ERROR: the boards disagree");
+    assert_eq!(indices_found, legacy_found, "ERROR: the boards disagree");
+
+    println!("{} rounds of 18 placements, each followed by in_mill() and can_move() for all 24 positions:", ROUNDS);
+    for (name, duration) in [("hashmap", legacy), ("bitboard", bitboard), ("bitboard (indices)", indices)].iter() {
+        println!("  {:<20} {:>8.1} ms  ({:.1}x)", name, duration.as_secs_f64() * 1000.0, legacy.as_secs_f64() / duration.as_secs_f64());
+    }
+}
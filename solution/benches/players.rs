@@ -0,0 +1,63 @@
+// Plays the Monte Carlo tree search player against the alpha-beta player, to compare their strength and speed.
+// Each pairing plays a few games with each player moving first. Run with `cargo bench --bench players`.
+
+use solution::{AlphaBeta, Color, Game, GameResult, Mcts, Player, GameView, Move};
+
+use std::time::{Duration, Instant};
+
+const GAMES: u64 = 4;
+
+// a player which keeps track of the time it took
+struct Timed<P: Player> {
+    player: P,
+    time: Duration,
+    moves: u32
+}
+
+impl<P: Player> Player for Timed<P> {
+    fn choose_move(&mut self, game: &GameView) -> Option<Move> {
+        let start = Instant::now();
+        let m = self.player.choose_move(game);
+        self.time += start.elapsed();
+        self.moves += 1;
+        m
+    }
+}
+
+fn timed<P: Player>(player: P) -> Timed<P> {
+    Timed { player, time: Duration::default(), moves: 0 }
+}
+
+fn main() {
+    println!("Monte Carlo tree search against alpha-beta, {} games each (wins/draws/losses for MCTS):", 2 * GAMES);
+
+    for (iterations, depth) in [(1000, 2), (1000, 4), (3000, 4)].iter() {
+        let (mut wins, mut draws, mut losses) = (0, 0, 0);
+        let mut mcts_time = (Duration::default(), 0);
+        let mut alpha_beta_time = (Duration::default(), 0);
+
+        for seed in 0..2 * GAMES {
+            let mut mcts = timed(Mcts::new(*iterations, seed));
+            let mut alpha_beta = timed(AlphaBeta::new(*depth));
+            let mcts_color = if seed % 2 == 0 { Color::White } else { Color::Black };
+
+            let mut game = Game::default();
+            let result = match mcts_color {
+                Color::White => game.play(&mut mcts, &mut alpha_beta),
+                Color::Black => game.play(&mut alpha_beta, &mut mcts)
+            };
+            match result {
+                GameResult::Win { winner, .. } if winner == mcts_color => wins += 1,
+                GameResult::Win { .. } => losses += 1,
+                GameResult::Draw { .. } => draws += 1
+            }
+
+            mcts_time = (mcts_time.0 + mcts.time, mcts_time.1 + mcts.moves);
+            alpha_beta_time = (alpha_beta_time.0 + alpha_beta.time, alpha_beta_time.1 + alpha_beta.moves);
+        }
+
+        let per_move = |(time, moves): (Duration, u32)| time.as_secs_f64() * 1000.0 / moves.max(1) as f64;
+        println!("  {:>5} simulations vs depth {}:  {}/{}/{}   ({:.1} ms vs {:.1} ms per move)",
+                 iterations, depth, wins, draws, losses, per_move(mcts_time), per_move(alpha_beta_time));
+    }
+}
@@ -0,0 +1,54 @@
+// Compares the number of positions visited by the alpha-beta search with and without a transposition table, on a few test positions.
+// Run with `cargo bench --bench search`.
+
+use solution::{AlphaBeta, Game, Rules, TranspositionTable, Replacement};
+
+use std::time::Instant;
+
+const DEPTH: u32 = 6;
+
+// the test positions - the start, the middle of the placing phase, the moving phase, and three men's morris
+fn positions() -> Vec<(&'static str, Game)> {
+    let play = |moves: &[&str]| {
+        let mut game = Game::default();
+        for m in moves {
+            game.make_move(&game.parse_move(m).unwrap()).unwrap();
+        }
+        game
+    };
+
+    vec![
+        ("start", Game::default()),
+        ("placing", play(&["a7", "b6", "g1", "d6", "d7", "f4"])),
+        ("moving", play(&["d1", "f4", "d2", "e5", "d3xe5", "g7", "b6", "c5", "f2", "g1", "b2xf4", "e4", "b4xc5", "g4xf2", "f2xe4", "f6", "e3", "d5"])),
+        ("three men's", Game::from_notation("1B1/WBW/1WB w m 0 0", Rules::three_mens_morris()).unwrap())
+    ]
+}
+
+fn main() {
+    // the name, size in megabytes and replacement scheme of each table
+    let tables = [
+        ("no table", 0, Replacement::DepthPreferred),
+        ("always replace", 16, Replacement::Always),
+        ("depth-preferred", 16, Replacement::DepthPreferred)
+    ];
+
+    println!("Positions visited by a search {} plies deep:", DEPTH);
+    for (name, game) in positions() {
+        println!("  {}:", name);
+
+        let mut plain_nodes = 0;
+        for (table_name, size, replacement) in tables.iter() {
+            let mut search = AlphaBeta::new(DEPTH).with_table(TranspositionTable::new(*size).with_replacement(*replacement));
+            let start = Instant::now();
+            search.best_move(&game).unwrap();
+            let elapsed = start.elapsed();
+
+            if plain_nodes == 0 {
+                plain_nodes = search.nodes;
+            }
+            println!("    {:<16} {:>10} nodes {:>8.1} ms  ({:.1}x)  score {}",
+                     table_name, search.nodes, elapsed.as_secs_f64() * 1000.0, plain_nodes as f64 / search.nodes as f64, search.score);
+        }
+    }
+}
@@ -0,0 +1,7 @@
+// a single action performed by the current player - one of these is passed to Game.apply()
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum Action {
+    Place(String),        // place a piece at a position (placing phase)
+    Move(String, String), // move a piece from the first position to the second (moving phase)
+    Remove(String)        // remove an opponent's piece from a position (after forming a mill)
+}
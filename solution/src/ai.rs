@@ -0,0 +1,536 @@
+use crate::game::Game;
+use crate::moves::{Move, MoveKind};
+use crate::point::Point;
+use crate::enums::{Color, GameResult};
+use crate::tablebase::{Tablebase, Value};
+use crate::transposition::{TranspositionTable, Bound, DEFAULT_SIZE};
+use crate::evaluation::Evaluation;
+use crate::zobrist;
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+// the score of a won position - wins found sooner (closer to the root of the search) score slightly higher
+pub const WIN: i32 = 1_000_000;
+
+// scores above this (or below its negative) are won (or lost) positions, rather than evaluations
+pub const DECIDED: i32 = WIN - 1000;
+
+// the depth searched by a search limited only by time
+pub const MAX_DEPTH: u32 = 64;
+
+// the time is checked once in this many positions, as reading the clock is much slower than visiting a position
+const CLOCK_INTERVAL: u64 = 1024;
+
+// The progress of a search, reported after every completed iteration.
+#[derive(Clone, Debug)]
+pub struct Info<'a> {
+    pub depth: u32,
+    pub score: i32,     // for the current player
+    pub nodes: u64,     // the positions visited so far, in all iterations
+    pub time: Duration,
+    pub pv: &'a [Move]  // the principal variation - the best move, followed by the moves expected in reply
+}
+
+// a function called with the progress of a search
+pub type InfoCallback = Box<dyn FnMut(&Info) + Send>;
+
+// A computer player choosing moves by a minimax search with alpha-beta pruning, looking the given number of moves (plies) ahead.
+// Each ply is a whole turn, including the capture after a mill, so all phases of the game are handled by Game.legal_moves().
+// The search deepens iteratively - it searches 1 ply ahead, then 2, and so on, so that it has a move ready when its time runs out,
+// and searches the principal variation of each iteration first in the next (which makes alpha-beta prune more).
+// The results of searching positions are kept in a transposition table, both across iterations and across searches,
+// so positions reached again by a different order of moves aren't searched again.
+pub struct AlphaBeta {
+    pub depth: u32,
+    pub nodes: u64,     // the number of positions visited by the last search
+    pub score: i32,     // the score of the move chosen by the last search, for the player who makes it
+    pub pv: Vec<Move>,  // the principal variation found by the last search
+
+    evaluation: Evaluation,             // the score of the positions at the end of the search
+    table: TranspositionTable,
+    tablebase: Option<Arc<Tablebase>>,  // the exact values of endgame positions, used instead of searching them
+    stop: Option<Arc<AtomicBool>>,      // set (e.g. by another thread) to stop the search early
+    time_limit: Option<Duration>,       // the time after which a search stops
+    info: Option<InfoCallback>,
+
+    deadline: Option<Instant>,  // when the current search has to stop
+    timed_out: bool,
+    previous: Vec<Move>,        // the principal variation of the previous iteration
+    pv_table: Vec<Vec<Move>>    // the principal variation found from each ply of the current line
+}
+
+impl AlphaBeta {
+    pub fn new(depth: u32) -> Self {
+        Self {
+            depth, nodes: 0, score: 0, pv: Vec::new(),
+            evaluation: Evaluation::default(), table: TranspositionTable::new(DEFAULT_SIZE), tablebase: None, stop: None, time_limit: None, info: None,
+            deadline: None, timed_out: false, previous: Vec::new(), pv_table: Vec::new()
+        }
+    }
+
+    // Returns the same search, scoring positions by the given evaluation (e.g. with weights read from a file).
+    pub fn with_evaluation(self, evaluation: Evaluation) -> Self {
+        Self { evaluation, ..self }
+    }
+
+    // Returns the same search, using the given transposition table (e.g. one of a different size, or of 0 megabytes to search without one).
+    pub fn with_table(self, table: TranspositionTable) -> Self {
+        Self { table, ..self }
+    }
+
+    // the transposition table, along with the results of the searches so far
+    pub fn table(&self) -> &TranspositionTable {
+        &self.table
+    }
+
+    // Returns the transposition table, e.g. to be used by a later search.
+    pub fn into_table(self) -> TranspositionTable {
+        self.table
+    }
+
+    // Returns the same search, which looks up the positions found in the tablebase instead of searching them.
+    pub fn with_tablebase(self, tablebase: Arc<Tablebase>) -> Self {
+        Self { tablebase: Some(tablebase), ..self }
+    }
+
+    // Returns the same search, which stops as soon as the flag is set.
+    pub fn with_stop(self, stop: Arc<AtomicBool>) -> Self {
+        Self { stop: Some(stop), ..self }
+    }
+
+    // Returns the same search, which stops after the given time (even if it hasn't reached its depth).
+    pub fn with_time_limit(self, time_limit: Duration) -> Self {
+        Self { time_limit: Some(time_limit), ..self }
+    }
+
+    // Returns the same search, which calls the given function after every iteration.
+    pub fn with_info(self, info: impl FnMut(&Info) + Send + 'static) -> Self {
+        Self { info: Some(Box::new(info)), ..self }
+    }
+
+    // Returns the best move for the current player of the game, or None if they have no legal moves.
+    // Among moves with equal scores, the first one searched is chosen, so the result is deterministic (unless it's stopped by time).
+    // If the search is stopped, the best of the moves searched completely is returned (or the first move, if none were).
+    pub fn best_move(&mut self, game: &Game) -> Option<Move> {
+        let start = Instant::now();
+        self.nodes = 0;
+        self.deadline = self.time_limit.map(|limit| start + limit);
+        self.timed_out = false;
+        self.previous.clear();
+        self.table.new_search();
+
+        let mut game = game.clone();
+        let mut best = None;
+        for depth in 1..=self.depth.clamp(1, MAX_DEPTH) {
+            let (m, score) = match self.search_root(&mut game, depth) {
+                Some(result) => result,
+                None => break
+            };
+            best = Some(m);
+            self.score = score;
+            self.pv = self.pv_table[0].clone();
+
+            // the best move of a stopped iteration is only used if it was searched completely - and so was the previous best move
+            if self.stopped() {
+                break;
+            }
+            if let Some(info) = &mut self.info {
+                info(&Info { depth, score, nodes: self.nodes, time: start.elapsed(), pv: &self.pv });
+            }
+            // a win or a loss which was found can't change with a deeper search
+            if score.abs() > DECIDED {
+                break;
+            }
+            self.previous = self.pv.clone();
+        }
+
+        best
+    }
+
+    // Searches all moves from the root to the given depth - returns the best one and its score, or None if there are no legal moves.
+    // When the search is stopped, returns the best move searched completely - the first move, if none were and there's no previous iteration.
+    fn search_root(&mut self, game: &mut Game, depth: u32) -> Option<(Move, i32)> {
+        self.pv_table.resize(MAX_DEPTH as usize + 2, Vec::new());
+        self.pv_table[0].clear();
+
+        let mut best = None;
+        let mut alpha = -WIN - 1;
+        for m in self.ordered_moves(game, 0, true, None) {
+            game.make_move(&m).expect("ERROR: generated an illegal move");
+            let score = -self.negamax(game, depth - 1, -WIN - 1, -alpha, 1, self.previous.first() == Some(&m));
+            game.undo().expect("ERROR: could not take back a searched move");
+
+            // the score of a move whose search was stopped is meaningless
+            let stopped = self.stopped();
+            if (best.is_none() && (!stopped || self.previous.is_empty())) || (score > alpha && !stopped) {
+                alpha = score;
+                best = Some(m);
+                self.update_pv(0, m);
+            }
+            if stopped {
+                break;
+            }
+        }
+
+        best.map(|m| (m, alpha))
+    }
+
+    fn stopped(&self) -> bool {
+        self.timed_out || self.stop.as_ref().is_some_and(|stop| stop.load(Ordering::Relaxed))
+    }
+
+    // Returns the score of the game from the point of view of its current player, searching the given number of plies ahead.
+    // Scores outside of the (alpha, beta) window are only bounds, which is enough for the caller to discard them.
+    // The moves leading to the position are the start of the previous iteration's principal variation if on_pv is set.
+    fn negamax(&mut self, game: &mut Game, depth: u32, mut alpha: i32, beta: i32, ply: i32, on_pv: bool) -> i32 {
+        self.nodes += 1;
+        self.pv_table[ply as usize].clear();
+
+        if self.nodes % CLOCK_INTERVAL == 0 && self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            self.timed_out = true;
+        }
+        if self.stopped() {
+            return 0;
+        }
+        if let Some(result) = game.outcome() {
+            return Self::score_result(result, game.current(), ply);
+        }
+        if let Some(value) = self.tablebase.as_ref().and_then(|tablebase| tablebase.probe(game)) {
+            return match value {
+                Value::Win(distance) => WIN - ply - distance as i32,
+                Value::Loss(distance) => -(WIN - ply - distance as i32),
+                Value::Draw => 0
+            };
+        }
+        if depth == 0 {
+            return self.evaluation.evaluate(game);
+        }
+
+        // a position searched at least as deep before can be scored from its entry, if the bound it has is enough for this window
+        let key = Self::key(game);
+        let entry = self.table.probe(key).copied();
+        if let Some(entry) = entry.filter(|entry| entry.depth as u32 >= depth) {
+            let score = entry.score_at(ply);
+            let enough = match entry.bound {
+                Bound::Exact => true,
+                Bound::Lower => score >= beta,
+                Bound::Upper => score <= alpha
+            };
+            if enough {
+                return score;
+            }
+        }
+
+        let original_alpha = alpha;
+        let mut best = None;
+        for m in self.ordered_moves(game, ply as usize, on_pv, entry.and_then(|entry| entry.best)) {
+            game.make_move(&m).expect("ERROR: generated an illegal move");
+            let score = -self.negamax(game, depth - 1, -beta, -alpha, ply + 1, on_pv && self.previous.get(ply as usize) == Some(&m));
+            game.undo().expect("ERROR: could not take back a searched move");
+
+            if score > alpha {
+                alpha = score;
+                best = Some(m);
+                self.update_pv(ply as usize, m);
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+
+        // the scores of a stopped search are meaningless, so they aren't stored
+        if !self.stopped() {
+            let bound = if alpha >= beta {
+                Bound::Lower
+            } else if alpha > original_alpha {
+                Bound::Exact
+            } else {
+                Bound::Upper
+            };
+            self.table.store(key, depth as u8, bound, TranspositionTable::score_from(alpha, ply), best);
+        }
+
+        alpha
+    }
+
+    // the principal variation from the ply is the move, followed by the principal variation found after it
+    fn update_pv(&mut self, ply: usize, m: Move) {
+        let (current, rest) = self.pv_table.split_at_mut(ply + 1);
+        current[ply].clear();
+        current[ply].push(m);
+        current[ply].extend_from_slice(&rest[0]);
+    }
+
+    // The legal moves, with captures first - they are usually the strongest, which lets alpha-beta prune more.
+    // Before them comes the best move found by an earlier search of the position (from the transposition table),
+    // and before that, on the previous iteration's principal variation, its move.
+    fn ordered_moves(&self, game: &Game, ply: usize, on_pv: bool, table_move: Option<Move>) -> Vec<Move> {
+        let mut moves = game.legal_moves();
+        moves.sort_by_key(|m| m.capture.is_none());
+
+        let pv_move = self.previous.get(ply).filter(|_| on_pv).copied();
+        for first in [table_move, pv_move].iter().flatten() {
+            if let Some(i) = moves.iter().position(|m| m == first) {
+                moves[..=i].rotate_right(1);
+            }
+        }
+        moves
+    }
+
+    // The key of the position in the transposition table - its hash, and unless the rules allow re-forming mills right away, the last move
+    // of each player, as it decides which mills don't count on their next turn (see Game.mills_formed()).
+    fn key(game: &Game) -> u64 {
+        if game.rules().reform_mills {
+            return game.hash();
+        }
+
+        let layout = game.board().layout();
+        let index = |point: Point| layout.index(&point.to_string()).expect("ERROR: invalid position on the board");
+        let players = [game.current().other(), game.current()];
+        game.history().iter().rev().zip(players.iter()).fold(game.hash(), |key, (m, player)| match m.kind {
+            MoveKind::Step(from, to) | MoveKind::Fly(from, to) => key ^ zobrist::last_move(*player, index(from), index(to)),
+            MoveKind::Place(_) => key
+        })
+    }
+
+    fn score_result(result: GameResult, current: Color, ply: i32) -> i32 {
+        match result {
+            GameResult::Win { winner, .. } if winner == current => WIN - ply,
+            GameResult::Win { .. } => -(WIN - ply),
+            GameResult::Draw { .. } => 0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn play(game: &mut Game, moves: &[&str]) {
+        for m in moves {
+            assert_eq!(game.make_move(&game.parse_move(m).unwrap()), Ok(()), "{}", m);
+        }
+    }
+
+    #[test]
+    fn test_completes_mill() {
+        let mut game = Game::default();
+        play(&mut game, &["a7", "b6", "d7", "d6"]);
+
+        // white can form a mill at g7 right away, and should capture one of black's two pieces which threaten to form a mill at f6
+        let m = AlphaBeta::new(1).best_move(&game).unwrap();
+        assert_eq!(m.to(), "g7".parse().unwrap());
+        assert!(m.capture.is_some());
+    }
+
+    #[test]
+    fn test_blocks_mill() {
+        let mut game = Game::default();
+        play(&mut game, &["a7", "b6", "g1", "d6"]);
+
+        // black threatens to form a mill at f6, which white should block (it can't form a mill of its own yet)
+        assert_eq!(AlphaBeta::new(2).best_move(&game), Some("f6".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_flying() {
+        let mut game = Game::default();
+        play(&mut game, &["d1", "f4", "d2", "e5", "d3xe5", "g7", "b6", "c5", "f2", "g1", "b2xf4", "e4", "b4xc5", "g4xf2", "f2xe4", "f6", "e3", "d5",
+                          "d3-c3", "g7-d7", "e3-d3xd5", "d7-d6", "c3-c4", "g4-g7", "d3-e3", "g7-d7", "e3-d3xd7", "f6*a4", "c4-c5", "a4*d5", "b4-c4"]);
+
+        // black has 3 pieces left, so it flies - and should complete a mill in the d column, capturing one of white's pieces
+        let mut search = AlphaBeta::new(1);
+        let m = search.best_move(&game).unwrap();
+        assert_eq!(m.kind, "g1*d7".parse::<Move>().unwrap().kind);
+        assert!(m.capture.is_some());
+        assert!(search.nodes > 0);
+    }
+
+    #[test]
+    fn test_no_moves() {
+        let mut game = Game::default();
+        game.resign(Color::White).unwrap();
+
+        assert_eq!(AlphaBeta::new(3).best_move(&game), None);
+    }
+
+    #[test]
+    fn test_self_play() {
+        let mut game = Game::default();
+        let mut white = AlphaBeta::new(2);
+        let mut black = AlphaBeta::new(1);
+
+        for _ in 0..40 {
+            let search = match game.current() {
+                Color::White => &mut white,
+                Color::Black => &mut black
+            };
+            match search.best_move(&game) {
+                None => break,
+                Some(m) => assert_eq!(game.make_move(&m), Ok(()))
+            }
+        }
+
+        assert!(game.history().len() >= 18);
+    }
+
+    #[test]
+    fn test_stop() {
+        let mut game = Game::default();
+        play(&mut game, &["a7", "b6", "d7", "d6"]);
+
+        // a search stopped before it starts still returns a legal move
+        let stop = Arc::new(AtomicBool::new(true));
+        let mut search = AlphaBeta::new(6).with_stop(stop.clone());
+        let m = search.best_move(&game).unwrap();
+        assert!(game.legal_moves().contains(&m));
+        assert!(search.nodes <= 1);
+
+        stop.store(false, Ordering::Relaxed);
+        assert_eq!(search.best_move(&game), AlphaBeta::new(6).best_move(&game));
+        assert!(search.score > 0);
+    }
+
+    #[test]
+    fn test_iterative_deepening() {
+        let mut game = Game::default();
+        play(&mut game, &["a7", "b6", "g1", "d6"]);
+
+        let reports = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut search = AlphaBeta::new(3).with_info({
+            let reports = reports.clone();
+            move |info: &Info| reports.lock().unwrap().push((info.depth, info.score, info.nodes, info.pv.to_vec()))
+        });
+        let m = search.best_move(&game).unwrap();
+
+        // every iteration is reported, with its principal variation of one move per ply
+        let reports = reports.lock().unwrap();
+        assert_eq!(reports.iter().map(|report| report.0).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert!(reports.iter().all(|(depth, _, _, pv)| pv.len() == *depth as usize));
+        assert!(reports.windows(2).all(|pair| pair[0].2 < pair[1].2));
+        assert_eq!(reports[2].3[0], m);
+        assert_eq!((reports[2].1, reports[2].2, &reports[2].3), (search.score, search.nodes, &search.pv));
+
+        // the principal variation is a line of legal moves
+        let mut line = game.clone();
+        for m in &search.pv {
+            assert_eq!(line.make_move(m), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_time_limit() {
+        let start = Instant::now();
+        let mut search = AlphaBeta::new(MAX_DEPTH).with_time_limit(Duration::from_millis(100));
+        let m = search.best_move(&Game::default()).unwrap();
+
+        assert!(Game::default().legal_moves().contains(&m));
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert!(!search.pv.is_empty());
+
+        // a won position ends the search as soon as the win is found
+        let game = Game::from_notation("WW1/BWB/B11 w m 0 0", crate::rules::Rules::three_mens_morris()).unwrap();
+        let mut search = AlphaBeta::new(MAX_DEPTH);
+        search.best_move(&game).unwrap();
+        assert_eq!(search.score, WIN - 1);
+        assert_eq!(search.pv.len(), 1);
+    }
+
+    #[test]
+    fn test_transposition_table() {
+        let mut placing = Game::default();
+        play(&mut placing, &["a7", "b6", "g1", "d6", "d7", "f4"]);
+        let mut moving = Game::default();
+        play(&mut moving, &["d1", "f4", "d2", "e5", "d3xe5", "g7", "b6", "c5", "f2", "g1", "b2xf4", "e4", "b4xc5", "g4xf2", "f2xe4", "f6", "e3", "d5"]);
+        let three = Game::from_notation("1B1/WBW/1WB w m 0 0", crate::rules::Rules::three_mens_morris()).unwrap();
+
+        // the same scores are found while visiting fewer positions
+        for game in [Game::default(), placing, moving, three].iter() {
+            let mut plain = AlphaBeta::new(5).with_table(TranspositionTable::new(0));
+            let mut search = AlphaBeta::new(5).with_table(TranspositionTable::new(1));
+            plain.best_move(game).unwrap();
+            search.best_move(game).unwrap();
+
+            assert_eq!(search.score, plain.score, "{}", game.to_notation());
+            assert!(search.nodes < plain.nodes, "{}: {} >= {}", game.to_notation(), search.nodes, plain.nodes);
+            assert!(!search.table().is_empty());
+            assert!(plain.table().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_table_key() {
+        use crate::rules::Rules;
+
+        // the same position, reached by the same moves in a different order
+        let notation = "W1W/B1B/3/6/3/B1B/W1W w m 0 0";
+        let moves = ["a7-d7", "b6-d6", "g1-d1", "f2-d2"];
+        for reform_mills in [true, false].iter() {
+            let rules = Rules { reform_mills: *reform_mills, ..Rules::default() };
+            let mut game = Game::from_notation(notation, rules.clone()).unwrap();
+            play(&mut game, &moves);
+            let mut other = Game::from_notation(notation, rules.clone()).unwrap();
+            play(&mut other, &[moves[2], moves[3], moves[0], moves[1]]);
+            assert_eq!(game.hash(), other.hash());
+
+            // unless mills can be re-formed right away, the last moves decide which mills count, so the search keeps the positions apart
+            assert_eq!(AlphaBeta::key(&game) == AlphaBeta::key(&other), *reform_mills);
+
+            // and the table doesn't change the scores it finds
+            let mut plain = AlphaBeta::new(4).with_table(TranspositionTable::new(0));
+            let mut search = AlphaBeta::new(4).with_table(TranspositionTable::new(1));
+            plain.best_move(&game).unwrap();
+            search.best_move(&game).unwrap();
+            assert_eq!(search.score, plain.score);
+        }
+    }
+
+    #[test]
+    fn test_evaluation() {
+        use crate::evaluation::{Feature, Stage};
+
+        let mut game = Game::default();
+        play(&mut game, &["a7", "b6", "d7", "d6"]);
+
+        // the search scores the positions at its depth by its evaluation
+        let mut doubled = Evaluation::default();
+        for feature in Feature::ALL.iter() {
+            doubled.set_weight(Stage::Placing, *feature, 2 * doubled.weight(Stage::Placing, *feature));
+        }
+        let mut search = AlphaBeta::new(1);
+        let mut doubled_search = AlphaBeta::new(1).with_evaluation(doubled);
+        assert_eq!(doubled_search.best_move(&game), search.best_move(&game));
+        assert_eq!(doubled_search.score, 2 * search.score);
+        assert!(search.score >= 100);
+    }
+
+    #[test]
+    fn test_tablebase() {
+        use crate::rules::Rules;
+
+        let rules = Rules::three_mens_morris();
+        let tablebase = Arc::new(Tablebase::generate(&rules, 3, 3));
+
+        // a position won in 9 moves (far beyond depth 1) - with the tablebase, both sides play it out perfectly
+        let mut game = Game::from_notation("1BB/WB1/WW1 w m 0 0", rules).unwrap();
+        let mut search = AlphaBeta::new(1).with_tablebase(tablebase.clone());
+        let mut distance = match tablebase.probe(&game) {
+            Some(Value::Win(distance)) => distance,
+            value => panic!("{:?}", value)
+        };
+        assert_eq!(distance, 9);
+
+        while !game.is_over() {
+            let m = search.best_move(&game).unwrap();
+            game.make_move(&m).unwrap();
+            distance -= 1;
+            if let Some(value) = tablebase.probe(&game) {
+                assert_eq!(value, if game.current() == Color::White { Value::Win(distance) } else { Value::Loss(distance) });
+            }
+        }
+        assert_eq!(distance, 0);
+        assert!(matches!(game.outcome(), Some(GameResult::Win { winner: Color::White, .. })));
+    }
+}
@@ -0,0 +1,82 @@
+use solution::{Rules, Layout, Tablebase};
+
+use std::env;
+use std::fs;
+use std::sync::Arc;
+use std::time::Instant;
+
+const USAGE: &str = "[--variant three|six|nine|twelve|morabaraba|lasker] [--board <file>] [--no-flying] [--unprotected-mills] <white pieces> <black pieces> <file>";
+
+// Generates the endgame tablebase of a variant, with up to the given numbers of pieces of each player, and writes it to a file
+// (to be used by the game with --tablebase). The options choose the rules the same way as for the game - a tablebase can only be used
+// with the rules it was generated for.
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let usage = || -> ! {
+        println!("Usage: {} {}", args[0], USAGE);
+        std::process::exit(1);
+    };
+
+    let mut rules = match args.iter().position(|arg| arg == "--variant") {
+        Some(i) => args.get(i + 1).and_then(|name| Rules::variant(name)).unwrap_or_else(|| usage()),
+        None => Rules::default()
+    };
+
+    let mut positional = Vec::new();
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--variant" => i += 1,
+            "--board" => {
+                let path = args.get(i + 1).unwrap_or_else(|| usage());
+                let text = fs::read_to_string(path).unwrap_or_else(|error| {
+                    println!("ERROR: Cannot read {} - {}", path, error);
+                    std::process::exit(1);
+                });
+                let layout = Layout::from_description(&text).unwrap_or_else(|error| {
+                    println!("ERROR: Invalid board in {} - {:?}!", path, error);
+                    std::process::exit(1);
+                });
+                rules.layout = Arc::new(layout);
+                i += 1;
+            }
+            "--no-flying" => rules.flying = false,
+            "--unprotected-mills" => rules.protect_mills = false,
+            arg => positional.push(arg)
+        }
+        i += 1;
+    }
+
+    let (white, black, path) = match positional.as_slice() {
+        [white, black, path] => match (white.parse::<u8>(), black.parse::<u8>()) {
+            (Ok(white), Ok(black)) if white >= 3 && black >= 3 => (white, black, *path),
+            _ => usage()
+        },
+        _ => usage()
+    };
+    if white as usize + black as usize > rules.layout.len() {
+        println!("ERROR: {} white and {} black pieces don't fit on a board of {} points!", white, black, rules.layout.len());
+        std::process::exit(1);
+    }
+    if !Tablebase::supports(&rules, white, black) {
+        println!("ERROR: {} white and {} black pieces have too many positions for a tablebase!", white, black);
+        std::process::exit(1);
+    }
+
+    let start = Instant::now();
+    let tablebase = Tablebase::generate(&rules, white, black);
+    println!("Generated in {:.1?}:", start.elapsed());
+    for w in 3..=white {
+        for b in 3..=black {
+            println!("  {} white, {} black - {} positions", w, b, tablebase.len(w, b));
+        }
+    }
+
+    match fs::write(path, tablebase.to_bytes()) {
+        Ok(()) => println!("Wrote the tablebase to {}.", path),
+        Err(error) => {
+            println!("ERROR: Cannot write {} - {}", path, error);
+            std::process::exit(1);
+        }
+    }
+}
@@ -1,82 +1,184 @@
-use std::collections::HashMap;
-
-use crate::node::Node;
+use crate::layout::Layout;
+use crate::point::Point;
 use crate::enums::Color;
 use crate::enums::State;
-use crate::enums::Direction;
+use crate::zobrist;
+use crate::symmetry::Transform;
 
 use crate::errors::PlacingError;
 use crate::errors::MovingError;
 use crate::errors::RemovingError;
 
-use splitmut::{SplitMut, SplitMutError};
+use std::sync::Arc;
 
+// The positions on the board are stored as two bitmasks, one for each color, with bit i set if position i of the layout is occupied by that color.
+// Adjacency and mills are precomputed by the layout, so all checks are a few bitwise operations.
 #[derive(Clone)]
 pub struct Board {
-    nodes: HashMap<&'static str, Node>
+    layout: Arc<Layout>,
+
+    white: u32,
+    black: u32,
+
+    hash: u64 // the Zobrist hash of the pieces on the board, updated with every change (see zobrist.rs)
 }
 
 impl Board {
+    // an empty nine men's morris board
     pub fn new() -> Self {
-        let nodes: HashMap<&str, Node> = [
-            // neighbours:   above       left        right       below
-            ("a7", Node::new(None,       None,       Some("d7"), Some("a4"))),
-            ("d7", Node::new(None,       Some("a7"), Some("g7"), Some("d6"))),
-            ("g7", Node::new(None,       Some("d7"), None,       Some("g4"))),
+        Self::with_layout(Layout::nine_mens_morris())
+    }
 
-            ("b6", Node::new(None,       None,       Some("d6"), Some("b4"))),
-            ("d6", Node::new(Some("d7"), Some("b6"), Some("f6"), Some("d5"))),
-            ("f6", Node::new(None,       Some("d6"), None,       Some("f4"))),
+    // an empty board with the given layout
+    pub fn with_layout(layout: Arc<Layout>) -> Self {
+        Self {
+            layout,
 
-            ("c5", Node::new(None,       None,       Some("d5"), Some("c4"))),
-            ("d5", Node::new(Some("d6"), Some("c5"), Some("e5"), None      )),
-            ("e5", Node::new(None,       Some("d5"), None,       Some("e4"))),
+            white: 0,
+            black: 0,
 
-            ("a4", Node::new(Some("a7"), None,       Some("b4"), Some("a1"))),
-            ("b4", Node::new(Some("b6"), Some("a4"), Some("c4"), Some("b2"))),
-            ("c4", Node::new(Some("c5"), Some("b4"), None,       Some("c3"))),
-            ("e4", Node::new(Some("e5"), None,       Some("f4"), Some("e3"))),
-            ("f4", Node::new(Some("f6"), Some("e4"), Some("g4"), Some("f2"))),
-            ("g4", Node::new(Some("g7"), Some("f4"), None,       Some("g1"))),
+            hash: 0
+        }
+    }
 
-            ("c3", Node::new(Some("c4"), None,       Some("d3"), None      )),
-            ("d3", Node::new(None,       Some("c3"), Some("e3"), Some("d2"))),
-            ("e3", Node::new(Some("e4"), Some("d3"), None,       None      )),
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
 
-            ("b2", Node::new(Some("b4"), None,       Some("d2"), None      )),
-            ("d2", Node::new(Some("d3"), Some("b2"), Some("f2"), Some("d1"))),
-            ("f2", Node::new(Some("f4"), Some("d2"), None,       None      )),
+    // Returns all positions on the board, in alphabetical order (so that iterating over them is deterministic).
+    pub fn positions(&self) -> Vec<&'static str> {
+        self.layout.names().to_vec()
+    }
 
-            ("a1", Node::new(Some("a4"), None,       Some("d1"), None      )),
-            ("d1", Node::new(Some("d2"), Some("a1"), Some("g1"), None      )),
-            ("g1", Node::new(Some("g4"), Some("d1"), None,       None      )),
-        ].iter().cloned().collect();
+    // Returns the state of the given position - None if it isn't on the board.
+    pub fn state(&self, position: &str) -> Option<State> {
+        self.layout.index(position).map(|index| self.state_at(index))
+    }
 
-        Self { nodes }
+    // Returns the unoccupied positions adjacent to the given one (an empty vector if it isn't on the board).
+    pub fn empty_neighbours(&self, position: &str) -> Vec<&'static str> {
+        match self.layout.index(position) {
+            None => Vec::new(),
+            Some(index) => self.indices(self.layout.adjacent(index) & self.empty()).map(|neighbour| self.layout.name(neighbour)).collect()
+        }
     }
+}
 
-    pub fn get_node_ref(&self, position: &str) -> Option<&Node> { // only used for testing
-        self.nodes.get(position)
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Board { // positions as indices and bitmasks - used where speed matters (e.g. move generation)
+    pub fn state_at(&self, index: usize) -> State {
+        if self.white & 1 << index != 0 {
+            State::Occupied(Color::White)
+        } else if self.black & 1 << index != 0 {
+            State::Occupied(Color::Black)
+        } else {
+            State::Empty
+        }
+    }
+
+    // a mask of the positions occupied by the given color
+    pub fn occupied(&self, color: Color) -> u32 {
+        match color {
+            Color::White => self.white,
+            Color::Black => self.black
+        }
+    }
+    // a mask of the unoccupied positions
+    pub fn empty(&self) -> u32 {
+        self.layout.all() & !(self.white | self.black)
+    }
+
+    pub fn point(&self, index: usize) -> Point {
+        self.layout.point(index)
+    }
+
+    // the Zobrist hash of the pieces on the board - Game.hash() adds the rest of the position
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    // Iterates over the indices of the positions in the given mask, in increasing order.
+    pub fn indices(&self, mask: u32) -> impl Iterator<Item = usize> {
+        let mut mask = mask;
+        std::iter::from_fn(move || {
+            if mask == 0 {
+                None
+            } else {
+                let index = mask.trailing_zeros() as usize;
+                mask &= mask - 1;
+                Some(index)
+            }
+        })
+    }
+
+    // Checks if the piece at the given index would be in a mill if the given color occupied the positions in the mask.
+    pub fn in_mill_with(&self, occupied: u32, index: usize) -> bool {
+        self.layout.mills_at(index).iter().any(|mill| occupied & mill == *mill)
+    }
+    // Counts the mills the piece at the given index would be part of if the given color occupied the positions in the mask.
+    pub fn mills_with(&self, occupied: u32, index: usize) -> usize {
+        self.layout.mills_at(index).iter().filter(|mill| occupied & *mill == **mill).count()
+    }
+    pub fn in_mill_at(&self, color: Color, index: usize) -> bool {
+        let occupied = self.occupied(color);
+        occupied & 1 << index != 0 && self.in_mill_with(occupied, index)
+    }
+    // a mask of the pieces of the given color which are currently in a mill
+    pub fn in_mills(&self, color: Color) -> u32 {
+        self.in_mills_with(self.occupied(color))
+    }
+    // a mask of the positions in the given mask which are part of a mill made only of positions in the mask
+    pub fn in_mills_with(&self, occupied: u32) -> u32 {
+        self.layout.mills().iter().filter(|mill| occupied & *mill == **mill).fold(0, |mask, mill| mask | mill)
+    }
+
+    fn occupied_mut(&mut self, color: Color) -> &mut u32 {
+        match color {
+            Color::White => &mut self.white,
+            Color::Black => &mut self.black
+        }
     }
 }
 
 use std::fmt::{self, Display, Formatter};
 impl Display for Board {
+    // Prints the diagram of the layout, with each position marked by its state.
+    // Layouts without a diagram are printed as a grid of their positions, without the lines between them.
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        writeln!(f, " 7 {}-----------{}-----------{}", self.nodes["a7"], self.nodes["d7"], self.nodes["g7"]).expect("ERROR when printing board");
-        writeln!(f, "   |           |           |").expect("ERROR when printing board");
-        writeln!(f, " 6 |   {}-------{}-------{}   |", self.nodes["b6"], self.nodes["d6"], self.nodes["f6"]).expect("ERROR when printing board");
-        writeln!(f, "   |   |       |       |   |").expect("ERROR when printing board");
-        writeln!(f, " 5 |   |   {}---{}---{}   |   |", self.nodes["c5"], self.nodes["d5"], self.nodes["e5"]).expect("ERROR when printing board");
-        writeln!(f, "   |   |   |       |   |   |").expect("ERROR when printing board");
-        writeln!(f, " 4 {}---{}---{}       {}---{}---{}", self.nodes["a4"], self.nodes["b4"], self.nodes["c4"], self.nodes["e4"], self.nodes["f4"], self.nodes["g4"]).expect("ERROR when printing board");
-        writeln!(f, "   |   |   |       |   |   |").expect("ERROR when printing board");
-        writeln!(f, " 3 |   |   {}---{}---{}   |   |", self.nodes["c3"], self.nodes["d3"], self.nodes["e3"]).expect("ERROR when printing board");  
-        writeln!(f, "   |   |       |       |   |").expect("ERROR when printing board");
-        writeln!(f, " 2 |   {}-------{}-------{}   |", self.nodes["b2"], self.nodes["d2"], self.nodes["f2"]).expect("ERROR when printing board");  
-        writeln!(f, "   |           |           |").expect("ERROR when printing board");
-        writeln!(f, " 1 {}-----------{}-----------{}", self.nodes["a1"], self.nodes["d1"], self.nodes["g1"]).expect("ERROR when printing board");  
-        writeln!(f, "   a   b   c   d   e   f   g").expect("ERROR when printing board");
+        let order: Vec<usize> = self.layout.rows().concat();
+
+        match self.layout.diagram() {
+            Some(diagram) => {
+                let mut states = order.iter().map(|&index| self.state_at(index));
+                for c in diagram.chars() {
+                    match c {
+                        '#' => write!(f, "{}", states.next().expect("ERROR when printing board"))?,
+                        _ => write!(f, "{}", c)?
+                    }
+                }
+            }
+            None => {
+                let files = order.iter().map(|&index| self.point(index).file).max().unwrap_or(0);
+                for row in self.layout.rows() {
+                    let rank = self.point(row[0]).rank;
+                    let mut line = format!("{:>2}", rank);
+                    for file in 0..=files {
+                        match row.iter().find(|&&index| self.point(index).file == file) {
+                            Some(&index) => line += &format!(" {}  ", self.state_at(index)),
+                            None => line += "    "
+                        }
+                    }
+                    writeln!(f, "{}", line.trim_end())?;
+                }
+                let letters: Vec<String> = (0..=files).map(|file| ((b'a' + file) as char).to_string()).collect();
+                writeln!(f, "   {}", letters.join("   "))?;
+            }
+        }
 
         Ok(())
     }
@@ -85,193 +187,127 @@ impl Display for Board {
 impl Board { // game logic - manipulating pieces
     // Places a piece of the given color at the given position (if the position isn't actually on the board, or it is but is already occupied - an error is returned).
     pub fn place_piece(&mut self, color: Color, position: &str) -> Result<(), PlacingError> {
-        match self.nodes.get_mut(position) {
-            None => Err(PlacingError::InvalidPosition),
-            Some(node) =>
-                match node.state {
-                    State::Occupied(_) => Err(PlacingError::PlaceAtOccupied),
-                    State::Empty => {
-                        node.state = State::Occupied(color);
-                        Ok(())
-                    }
-                }
+        let index = self.layout.index(position).ok_or(PlacingError::InvalidPosition)?;
+        if self.empty() & 1 << index == 0 {
+            return Err(PlacingError::PlaceAtOccupied);
         }
+
+        *self.occupied_mut(color) |= 1 << index;
+        self.hash ^= zobrist::piece(color, index);
+        Ok(())
     }
 
     // Moves a piece of a given color between two positions
     // (if either of the positions isn't on the board, the first one isn't occupied by a piece of the given color, or the second one isn't empty - an error is returned).
     // The boolean argument determines whether the piece can "fly" - if not, a check is made for adjacency between the positions (and an error is returned if it fails).
     pub fn move_piece(&mut self, color: Color, from: &str, to: &str, flying: bool) -> Result<(), MovingError> {
-        let (start, end) = self.nodes.get2_mut(from, to);
-        match start {
-            Err(_) => Err(MovingError::InvalidMoveFrom),
-            Ok(start) =>
-                match end {
-                    Err(SplitMutError::SameValue) => Err(MovingError::MoveToSame),
-                    Err(SplitMutError::NoValue) => Err(MovingError::InvalidMoveTo),
-                    Ok(end) => {
-                        match &start.state {
-                            State::Empty => Err(MovingError::MoveFromEmpty),
-                            State::Occupied(start_color) => {
-                                if *start_color != color {
-                                    return Err(MovingError::MoveFromWrongColor);
-                                } 
-
-                                match &end.state {
-                                    State::Occupied(_) => Err(MovingError::MoveToOccupied),
-                                    State::Empty => {
-                                        if flying == false && start.adjacent().contains(&to) == false {
-                                            return Err(MovingError::NotAdjacent);
-                                        }
-
-                                        start.state = State::Empty;
-                                        end.state = State::Occupied(color);
-                                        Ok(())
-                                    }
-                                }
-                            }
-                        }
-                    }
-            }
+        let start = self.layout.index(from).ok_or(MovingError::InvalidMoveFrom)?;
+        if from == to {
+            return Err(MovingError::MoveToSame);
+        }
+        let end = self.layout.index(to).ok_or(MovingError::InvalidMoveTo)?;
+
+        match self.state_at(start) {
+            State::Empty => return Err(MovingError::MoveFromEmpty),
+            State::Occupied(start_color) if start_color != color => return Err(MovingError::MoveFromWrongColor),
+            State::Occupied(_) => {}
         }
+        if self.state_at(end) != State::Empty {
+            return Err(MovingError::MoveToOccupied);
+        }
+        if !flying && self.layout.adjacent(start) & 1 << end == 0 {
+            return Err(MovingError::NotAdjacent);
+        }
+
+        *self.occupied_mut(color) ^= 1 << start | 1 << end;
+        self.hash ^= zobrist::piece(color, start) ^ zobrist::piece(color, end);
+        Ok(())
     }
 
     // Removes a piece of the given color from the given position
     // (if the position isn't actually on the board, or there isn't a piece of the given color on it, an error is returned;
     // if 'check_for_mills' is true, and an attempt is made to remove a piece that is currently in a mill, an error is returned).
     pub fn remove_piece(&mut self, color: Color, from: &str, check_for_mills: bool) -> Result<(), RemovingError> {
-        let position_in_mill = self.in_mill(color, from);
-
-        match self.nodes.get_mut(from) {
-            None => Err(RemovingError::InvalidPosition),
-            Some(node) =>
-                match &node.state {
-                    State::Empty => Err(RemovingError::RemoveFromEmpty),
-                    State::Occupied(node_color) => {
-                        if *node_color != color {
-                            return Err(RemovingError::RemoveFromWrongColor);
-                        }
-
-                        /*if check_for_mills {
-                            if self.in_mill(color, from) {
-                                return Err(RemovingError::RemoveFromMill);
-                            }
-                        }*/
-                        if check_for_mills && position_in_mill {
-                            return Err(RemovingError::RemoveFromMill);
-                        }
-
-                        node.state = State::Empty;
-                        Ok(())
-                    }
-                        
+        let index = self.layout.index(from).ok_or(RemovingError::InvalidPosition)?;
+
+        match self.state_at(index) {
+            State::Empty => Err(RemovingError::RemoveFromEmpty),
+            State::Occupied(node_color) if node_color != color => Err(RemovingError::RemoveFromWrongColor),
+            State::Occupied(_) => {
+                if check_for_mills && self.in_mill_at(color, index) {
+                    return Err(RemovingError::RemoveFromMill);
                 }
+
+                *self.occupied_mut(color) &= !(1 << index);
+                self.hash ^= zobrist::piece(color, index);
+                Ok(())
+            }
         }
     }
 
     // Checks if a piece of the given color can move from the given position (i.e. if there are any unoccupied positions adjacent to it).
     pub fn can_move(&self, color: Color, position: &str) -> bool {
-        if let Some(node) = self.nodes.get(position)  {
-            if node.state == State::Occupied(color) {
-                for neighbour in node.adjacent() {
-                    if let Some(neighbour_node) = self.nodes.get(neighbour) {
-                        if neighbour_node.state == State::Empty {
-                            return true;
-                        }
-                    }
-                }
-            } 
+        match self.layout.index(position) {
+            Some(index) => self.occupied(color) & 1 << index != 0 && self.layout.adjacent(index) & self.empty() != 0,
+            None => false
         }
-
-        false
     }
 }
 
-impl Board { // game logic - mills
-    // Checks if a piece is in a mill, by calling the middle_of_mill() and edge_of_mill() functions
-    pub fn in_mill(&self, color: Color, position: &str) -> bool {
-        if let Some(node) = self.nodes.get(position) {
-            return self.middle_of_mill(color, *node) || self.edge_of_mill(color, *node)
-        }
-
-        false
-    }
-
-    // Checks if a piece is in the middle of mill, i.e. if it forms a mill with two of it's neighbours (either the ones to the left and to the right, or above and below).
-    // Only called by the in_mill() function.
-    fn middle_of_mill(&self, color: Color, node: Node) -> bool {
-        // check for horizontal mill
-        if let Some(left) = node.left {
-            if let Some(right) = node.right {
-                if let Some(left_node) = self.nodes.get(left) {
-                    if let Some(right_node) = self.nodes.get(right) {
-                        if left_node.state == State::Occupied(color) && right_node.state == State::Occupied(color) {
-                            return true;
-                        }
-                    }
-                }
-            }
-        }
+impl Board { // symmetries
+    // Returns the board with its pieces moved by the given transform - None if the transform isn't a symmetry of the layout.
+    pub fn transform(&self, transform: Transform) -> Option<Board> {
+        let white = self.layout.transform_mask(transform, self.white)?;
+        let black = self.layout.transform_mask(transform, self.black)?;
 
-        // check for vertical mill
-        if let Some(above) = node.above {
-            if let Some(below) = node.below {
-                if let Some(above_node) = self.nodes.get(above) {
-                    if let Some(below_node) = self.nodes.get(below) {
-                        if above_node.state == State::Occupied(color) && below_node.state == State::Occupied(color) {
-                            return true;
-                        }
-                    }
-                }
-            }
+        let mut board = Self::with_layout(self.layout.clone());
+        for (color, mask) in [(Color::White, white), (Color::Black, black)].iter() {
+            *board.occupied_mut(*color) = *mask;
+            board.hash ^= board.indices(*mask).fold(0, |hash, index| hash ^ zobrist::piece(*color, index));
         }
-
-        false
+        Some(board)
     }
 
-    // Checks if a piece is the edge of mill, i.e. if it forms a mill with a neighbour and that neighbour's neighbour in one of the four directions.
-    // Only called by the in_mill() function.
-    fn edge_of_mill(&self, color: Color, node: Node) -> bool {
-        self.check_direction(color, node, Direction::Above, 1) ||
-        self.check_direction(color, node, Direction::Left,  1) ||
-        self.check_direction(color, node, Direction::Right, 1) ||
-        self.check_direction(color, node, Direction::Below, 1)
+    // Returns the canonical representative of the board among all of its symmetric versions (the one with the smallest masks),
+    // along with the transform which maps this board to it. Boards which are symmetric to each other have the same canonical board.
+    pub fn canonical(&self) -> (Board, Transform) {
+        self.layout.symmetries()
+            .filter_map(|transform| self.transform(transform).map(|board| (board, transform)))
+            .min_by_key(|(board, _)| (board.white, board.black))
+            .expect("ERROR: the identity is always a symmetry")
     }
-    // Checks if the given node forms a mill in the given direction (i.e. the neighbour and that neighbour's neighbour in said direction are the same color).
-    // The 'found' parameter has to be 1 at the first call.
-    fn check_direction(&self, color: Color, node: Node, direction: Direction, found: u8) -> bool {
-        if found == 3 {
-            return true;
-        }
+}
 
-        if let Some(neighbour) = node.get_neighbour(direction) {
-            if let Some(neighbour_node) = self.nodes.get(neighbour) {
-                return neighbour_node.state == State::Occupied(color) && self.check_direction(color, *neighbour_node, direction, found + 1)
-            }
+impl Board { // game logic - mills
+    // Checks if a piece of the given color at the given position is in a mill, i.e. if any of the lines through that position is fully occupied by the color.
+    pub fn in_mill(&self, color: Color, position: &str) -> bool {
+        match self.layout.index(position) {
+            Some(index) => self.in_mill_at(color, index),
+            None => false
         }
-
-        false
     }
 }
 
 
 #[cfg(test)]
+#[allow(clippy::bool_comparison)] // the tests compare with false explicitly
 mod tests {
     use super::*;
+    use crate::layout::Layout;
 
     #[test]
     fn test_place() {
         let mut board = Board::new();
 
         assert_eq!(board.place_piece(Color::White, "a7"), Ok(()));
-        let a7 = board.nodes.get("a7");
+        let a7 = board.state("a7");
         assert!(a7.is_some());
-        assert_eq!(a7.unwrap().state, State::Occupied(Color::White));
+        assert_eq!(a7.unwrap(), State::Occupied(Color::White));
 
         assert_eq!(board.place_piece(Color::Black, "a4"), Ok(()));
-        let a4 = board.nodes.get("a4");
+        let a4 = board.state("a4");
         assert!(a4.is_some());
-        assert_eq!(a4.unwrap().state, State::Occupied(Color::Black));
+        assert_eq!(a4.unwrap(), State::Occupied(Color::Black));
 
         assert_eq!(board.place_piece(Color::White, "a7"), Err(PlacingError::PlaceAtOccupied));
         assert_eq!(board.place_piece(Color::Black, "a7"), Err(PlacingError::PlaceAtOccupied));
@@ -294,21 +330,21 @@ mod tests {
         assert_eq!(board.move_piece(Color::White, "a7", "a1", false), Err(MovingError::NotAdjacent));
 
         assert_eq!(board.move_piece(Color::White, "a7", "d7", false), Ok(()));
-        assert_eq!(board.nodes.get("a7").unwrap().state, State::Empty);
-        assert_eq!(board.nodes.get("d7").unwrap().state, State::Occupied(Color::White));
+        assert_eq!(board.state("a7").unwrap(), State::Empty);
+        assert_eq!(board.state("d7").unwrap(), State::Occupied(Color::White));
 
         assert_eq!(board.move_piece(Color::White, "d7", "d1", true), Ok(()));
-        assert_eq!(board.nodes.get("d7").unwrap().state, State::Empty);
-        assert_eq!(board.nodes.get("d1").unwrap().state, State::Occupied(Color::White));
+        assert_eq!(board.state("d7").unwrap(), State::Empty);
+        assert_eq!(board.state("d1").unwrap(), State::Occupied(Color::White));
 
 
         assert_eq!(board.move_piece(Color::Black, "g1", "g4", true), Ok(()));
-        assert_eq!(board.nodes.get("g1").unwrap().state, State::Empty);
-        assert_eq!(board.nodes.get("g4").unwrap().state, State::Occupied(Color::Black));
+        assert_eq!(board.state("g1").unwrap(), State::Empty);
+        assert_eq!(board.state("g4").unwrap(), State::Occupied(Color::Black));
 
         assert_eq!(board.move_piece(Color::Black, "g4", "a1", true), Ok(()));
-        assert_eq!(board.nodes.get("g4").unwrap().state, State::Empty);
-        assert_eq!(board.nodes.get("a1").unwrap().state, State::Occupied(Color::Black));
+        assert_eq!(board.state("g4").unwrap(), State::Empty);
+        assert_eq!(board.state("a1").unwrap(), State::Occupied(Color::Black));
     }
     #[test]
     fn test_remove() {
@@ -326,13 +362,13 @@ mod tests {
         assert_eq!(board.remove_piece(Color::White, "a7", true), Err(RemovingError::RemoveFromMill));
 
         assert_eq!(board.remove_piece(Color::White, "a7", false), Ok(()));
-        assert_eq!(board.nodes.get("a7").unwrap().state, State::Empty);
+        assert_eq!(board.state("a7").unwrap(), State::Empty);
 
         assert_eq!(board.remove_piece(Color::White, "a4", true), Ok(()));
-        assert_eq!(board.nodes.get("a4").unwrap().state, State::Empty);
+        assert_eq!(board.state("a4").unwrap(), State::Empty);
 
         assert_eq!(board.remove_piece(Color::Black, "g1", true), Ok(()));
-        assert_eq!(board.nodes.get("g1").unwrap().state, State::Empty);
+        assert_eq!(board.state("g1").unwrap(), State::Empty);
     }
 
     #[test]
@@ -358,6 +394,25 @@ mod tests {
         assert!(board.can_move(Color::White, "a1") == false);
     }
 
+    #[test]
+    fn test_positions() {
+        let mut board = Board::new();
+
+        let positions = board.positions();
+        assert_eq!(positions.len(), 24);
+        assert_eq!(positions[..4], ["a1", "a4", "a7", "b2"]);
+
+        assert_eq!(board.place_piece(Color::White, "a7"), Ok(()));
+        assert_eq!(board.place_piece(Color::Black, "a4"), Ok(()));
+        assert_eq!(board.state("a7"), Some(State::Occupied(Color::White)));
+        assert_eq!(board.state("a1"), Some(State::Empty));
+        assert_eq!(board.state("a8"), None);
+
+        assert_eq!(board.empty_neighbours("a7"), vec!["d7"]);
+        assert_eq!(board.empty_neighbours("a4"), vec!["a1", "b4"]);
+        assert_eq!(board.empty_neighbours("a8"), Vec::<&str>::new());
+    }
+
     #[test]
     fn test_mills() {
         let mut board = Board::new();
@@ -389,7 +444,7 @@ mod tests {
         assert!(board.in_mill(Color::Black, "d3") == false);
     }
     #[test]
-    fn test_edge_of_mill() {
+    fn test_in_mills() {
         let mut board = Board::new();
 
         assert_eq!(board.place_piece(Color::White, "a7"), Ok(()));
@@ -399,41 +454,120 @@ mod tests {
         assert_eq!(board.place_piece(Color::White, "g1"), Ok(()));
         assert_eq!(board.place_piece(Color::White, "g7"), Ok(()));
 
+        let in_mills: Vec<&str> = board.indices(board.in_mills(Color::White)).map(|index| board.layout().name(index)).collect();
+        assert_eq!(in_mills, vec!["a1", "a4", "a7", "d1", "g1"]);
+        assert_eq!(board.in_mills(Color::Black), 0);
 
-        assert!(board.edge_of_mill(Color::White, *board.nodes.get("a7").unwrap()));
-        assert!(board.edge_of_mill(Color::White, *board.nodes.get("a4").unwrap()) == false);
-        assert!(board.edge_of_mill(Color::White, *board.nodes.get("a1").unwrap()));
-        assert!(board.edge_of_mill(Color::White, *board.nodes.get("d1").unwrap()) == false);
-        assert!(board.edge_of_mill(Color::White, *board.nodes.get("g1").unwrap()));
-        assert!(board.edge_of_mill(Color::White, *board.nodes.get("g7").unwrap()) == false);
-
-        assert!(board.check_direction(Color::White, *board.nodes.get("a7").unwrap(), Direction::Above, 1) == false);
-        assert!(board.check_direction(Color::White, *board.nodes.get("a7").unwrap(), Direction::Left, 1) == false);
-        assert!(board.check_direction(Color::White, *board.nodes.get("a7").unwrap(), Direction::Right, 1) == false);
-        assert!(board.check_direction(Color::White, *board.nodes.get("a7").unwrap(), Direction::Below, 1));
+        // g7 would form a mill if g4 was also occupied
+        let g4 = board.layout().index("g4").unwrap();
+        let g7 = board.layout().index("g7").unwrap();
+        assert!(!board.in_mill_at(Color::White, g7));
+        assert!(board.in_mill_with(board.occupied(Color::White) | 1 << g4, g7));
+        assert!(!board.in_mill_with(board.occupied(Color::White) | 1 << g4, board.layout().index("d7").unwrap()));
+    }
+    #[test]
+    fn test_indices() {
+        let board = Board::new();
 
-        assert!(board.check_direction(Color::White, *board.nodes.get("a4").unwrap(), Direction::Above, 1) == false);
-        assert!(board.check_direction(Color::White, *board.nodes.get("a4").unwrap(), Direction::Below, 1) == false);
+        assert_eq!(board.indices(0).count(), 0);
+        assert_eq!(board.indices(0b1011).collect::<Vec<usize>>(), vec![0, 1, 3]);
+        assert_eq!(board.indices(board.empty()).count(), 24);
+        assert_eq!(board.indices(1 << 31).collect::<Vec<usize>>(), vec![31]);
+    }
 
-        assert!(board.check_direction(Color::White, *board.nodes.get("a1").unwrap(), Direction::Right, 1));
-        assert!(board.check_direction(Color::White, *board.nodes.get("g1").unwrap(), Direction::Left, 1));
+    #[test]
+    fn test_hash() {
+        let mut board = Board::new();
+        assert_eq!(board.hash(), 0);
+
+        board.place_piece(Color::White, "a7").unwrap();
+        board.place_piece(Color::Black, "a4").unwrap();
+        let placed = board.hash();
+        assert_ne!(placed, 0);
+
+        // failed changes leave the hash alone
+        assert!(board.move_piece(Color::White, "a7", "g1", false).is_err());
+        assert!(board.remove_piece(Color::White, "a4", false).is_err());
+        assert_eq!(board.hash(), placed);
+
+        board.move_piece(Color::White, "a7", "d7", false).unwrap();
+        assert_ne!(board.hash(), placed);
+        board.move_piece(Color::White, "d7", "a7", false).unwrap();
+        assert_eq!(board.hash(), placed);
+
+        board.remove_piece(Color::Black, "a4", false).unwrap();
+        board.remove_piece(Color::White, "a7", false).unwrap();
+        assert_eq!(board.hash(), 0);
     }
+
     #[test]
-    fn test_middle_of_mill() {
+    fn test_symmetries() {
         let mut board = Board::new();
+        board.place_piece(Color::White, "a7").unwrap();
+        board.place_piece(Color::White, "d7").unwrap();
+        board.place_piece(Color::Black, "d5").unwrap();
+
+        let rotated = board.transform(Transform { rotation: 1, ..Transform::IDENTITY }).unwrap();
+        assert_eq!(rotated.state("a1"), Some(State::Occupied(Color::White)));
+        assert_eq!(rotated.state("a4"), Some(State::Occupied(Color::White)));
+        assert_eq!(rotated.state("c4"), Some(State::Occupied(Color::Black)));
+        assert_eq!(rotated.state("a7"), Some(State::Empty));
+
+        let mut expected = Board::new();
+        for (color, position) in [(Color::White, "a1"), (Color::White, "a4"), (Color::Black, "c4")].iter() {
+            expected.place_piece(*color, position).unwrap();
+        }
+        assert_eq!(rotated.hash(), expected.hash());
+
+        // all symmetric versions of a board have the same canonical board, and the transform maps each of them to it
+        let (canonical, _) = board.canonical();
+        for transform in board.layout().symmetries() {
+            let symmetric = board.transform(transform).unwrap();
+            let (other, used) = symmetric.canonical();
+            assert_eq!((other.occupied(Color::White), other.occupied(Color::Black)), (canonical.occupied(Color::White), canonical.occupied(Color::Black)));
+            assert_eq!(other.hash(), canonical.hash());
+            assert_eq!(symmetric.transform(used).unwrap().hash(), canonical.hash());
+            assert_eq!(symmetric.transform(transform.inverse()).unwrap().hash(), board.hash());
+        }
 
-        assert_eq!(board.place_piece(Color::White, "a7"), Ok(()));
-        assert_eq!(board.place_piece(Color::White, "a4"), Ok(()));
-        assert_eq!(board.place_piece(Color::White, "a1"), Ok(()));
-        assert_eq!(board.place_piece(Color::White, "d1"), Ok(()));
-        assert_eq!(board.place_piece(Color::White, "g1"), Ok(()));
-        assert_eq!(board.place_piece(Color::White, "g7"), Ok(()));
+        let board = Board::with_layout(Layout::three_mens_morris());
+        assert!(board.transform(Transform { swap_squares: true, ..Transform::IDENTITY }).is_none());
+    }
 
-        assert!(board.middle_of_mill(Color::White, *board.nodes.get("a7").unwrap()) == false);
-        assert!(board.middle_of_mill(Color::White, *board.nodes.get("a4").unwrap()));
-        assert!(board.middle_of_mill(Color::White, *board.nodes.get("a1").unwrap()) == false);
-        assert!(board.middle_of_mill(Color::White, *board.nodes.get("d1").unwrap()));
-        assert!(board.middle_of_mill(Color::White, *board.nodes.get("g1").unwrap()) == false);
-        assert!(board.middle_of_mill(Color::White, *board.nodes.get("g7").unwrap()) == false);
+    #[test]
+    fn test_display() {
+        let mut board = Board::new();
+        board.place_piece(Color::White, "a7").unwrap();
+        board.place_piece(Color::Black, "g1").unwrap();
+        board.place_piece(Color::White, "e4").unwrap();
+
+        let text = board.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 14);
+        assert_eq!(lines[0], " 7 ○-----------·-----------·");
+        assert_eq!(lines[6], " 4 ·---·---·       ○---·---·");
+        assert_eq!(lines[12], " 1 ·-----------·-----------●");
+        assert_eq!(lines[13], "   a   b   c   d   e   f   g");
+
+        let mut board = Board::with_layout(Layout::twelve_mens_morris());
+        board.place_piece(Color::Black, "b6").unwrap();
+        let text = board.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[1], "   | \\         |         / |");
+        assert_eq!(lines[2], " 6 |   ●-------·-------·   |");
+    }
+    #[test]
+    fn test_display_without_diagram() {
+        use crate::node::Node;
+        use std::sync::Arc;
+
+        let layout = Layout::new(&[
+            ("a2", Node::new(None, None, Some("b2"), Some("a1"))),
+            ("b2", Node::new(None, Some("a2"), None, None)),
+            ("a1", Node::new(Some("a2"), None, None, None)),
+        ]);
+        let mut board = Board::with_layout(Arc::new(layout));
+        board.place_piece(Color::White, "b2").unwrap();
+        assert_eq!(board.to_string(), " 2 ·   ○\n 1 ·\n   a   b\n");
     }
 }
\ No newline at end of file
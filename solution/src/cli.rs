@@ -0,0 +1,534 @@
+use solution::{Game, Action, Color, Phase, AlphaBeta, Rules, Layout, Tablebase, Evaluation};
+use solution::{GameError, PlacingError, MovingError, RemovingError, LoadError, LayoutError, TablebaseError, EvaluationError};
+
+use std::io;
+use std::io::BufRead;
+use std::fs;
+use std::sync::Arc;
+
+// Reads a game saved with the "save" command from the given file, to be continued with the given rules, printing an error if that fails.
+pub fn load_game(path: &str, rules: Rules) -> Option<Game> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(error) => {
+            println!("ERROR: Cannot read {} - {}", path, error);
+            return None;
+        }
+    };
+
+    match Game::load(&text, rules) {
+        Ok(game) => Some(game),
+        Err(LoadError::MissingFirst)              => { println!("ERROR: {} doesn't say which player moved first!", path); None }
+        Err(LoadError::InvalidMove(line))         => { println!("ERROR: Invalid move on line {} of {}!", line, path); None }
+        Err(LoadError::IllegalMove(line, error))  => { println!("ERROR: Illegal move on line {} of {} - {:?}!", line, path, error); None }
+        Err(LoadError::InvalidResult(line))       => { println!("ERROR: Invalid result on line {} of {}!", line, path); None }
+    }
+}
+
+// Reads the description of a custom board from the given file (see Layout.from_description()), printing an error if that fails.
+pub fn load_layout(path: &str) -> Option<Layout> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(error) => {
+            println!("ERROR: Cannot read {} - {}", path, error);
+            return None;
+        }
+    };
+
+    match Layout::from_description(&text) {
+        Ok(layout) => Some(layout),
+        Err(LayoutError::TooManyPoints)   => { println!("ERROR: The board in {} has more than 32 points!", path); None }
+        Err(LayoutError::InvalidDiagram)  => { println!("ERROR: The diagram in {} doesn't have a '#' for every point!", path); None }
+        Err(error)                        => { println!("ERROR: Invalid board in {} - {:?} (the number is the line)!", path, error); None }
+    }
+}
+
+// Reads an endgame tablebase written by the tablebase tool from the given file, to be used with the given rules, printing an error if that fails.
+pub fn load_tablebase(path: &str, rules: &Rules) -> Option<Tablebase> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(error) => {
+            println!("ERROR: Cannot read {} - {}", path, error);
+            return None;
+        }
+    };
+
+    match Tablebase::from_bytes(&bytes, rules) {
+        Ok(tablebase) => Some(tablebase),
+        Err(TablebaseError::InvalidFormat)  => { println!("ERROR: {} isn't a tablebase!", path); None }
+        Err(TablebaseError::RulesMismatch)  => { println!("ERROR: The tablebase in {} was generated for different rules!", path); None }
+    }
+}
+
+// Reads the weights of the computer's evaluation from the given file (see Evaluation.from_config()), printing an error if that fails.
+pub fn load_evaluation(path: &str) -> Option<Evaluation> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(error) => {
+            println!("ERROR: Cannot read {} - {}", path, error);
+            return None;
+        }
+    };
+
+    match Evaluation::from_config(&text) {
+        Ok(evaluation) => Some(evaluation),
+        Err(EvaluationError::UnknownStage(line))    => { println!("ERROR: Unknown stage on line {} of {}!", line, path); None }
+        Err(EvaluationError::UnknownFeature(line))  => { println!("ERROR: Unknown feature on line {} of {}!", line, path); None }
+        Err(EvaluationError::InvalidWeight(line))   => { println!("ERROR: Invalid weight on line {} of {}!", line, path); None }
+        Err(EvaluationError::InvalidLine(line))     => { println!("ERROR: Invalid line {} of {}!", line, path); None }
+    }
+}
+
+// Checks if the input is one of the commands that can be given instead of a position - "undo", "redo", "resign",
+// "save <file>" or "load <file>" - and executes it.
+// Returns true if a command was executed (or attempted), in which case the game's state might have changed.
+fn command(game: &mut Game, input: &str) -> bool {
+    if let Some(path) = input.strip_prefix("save ") {
+        match fs::write(path.trim(), game.save()) {
+            Ok(()) => println!("Saved the game to {}.", path.trim()),
+            Err(error) => println!("ERROR: Cannot write {} - {}", path.trim(), error)
+        }
+        return true;
+    }
+    if let Some(path) = input.strip_prefix("load ") {
+        if let Some(loaded) = load_game(path.trim(), game.rules().clone()) {
+            *game = loaded;
+            println!("Loaded the game from {}.", path.trim());
+        }
+        return true;
+    }
+
+    match input {
+        "undo" => {
+            match game.undo() {
+                Ok(()) => println!("Took back the last move."),
+                Err(_) => println!("ERROR: There are no moves to undo!")
+            }
+            true
+        }
+        "redo" => {
+            match game.redo() {
+                Ok(()) => println!("Played the last undone move again."),
+                Err(_) => println!("ERROR: There are no moves to redo!")
+            }
+            true
+        }
+        "resign" => {
+            let _ = game.resign(game.current());
+            true
+        }
+        _ => false
+    }
+}
+
+// Requests the coordinates of a single position, reads them from the standard input and applies Action::Place with said position.
+// Handles the game's errors and prints an approriate message if there is one.
+// If the placement is successful, returns the position. If a command is given instead, returns None.
+fn place_piece<R: BufRead>(game: &mut Game, mut input: R) -> Option<String> {
+    println!("{} player, PLACE your piece:", game.current());
+
+    loop {
+        let mut position = String::new();
+        match input.read_line(&mut position) {
+            Err(error) => println!("ERROR: input error - {}", error),
+            Ok(_) => {
+                let trimmed_position = position.trim_end();
+                if command(game, trimmed_position) {
+                    return None;
+                }
+
+                if apply_place(game, trimmed_position) {
+                    return Some(trimmed_position.to_string());
+                }
+            }
+        }
+    }
+}
+
+// Applies Action::Place with the given position, printing an appropriate message if there is an error - returns true if it succeeds.
+fn apply_place(game: &mut Game, position: &str) -> bool {
+    match game.apply(Action::Place(position.to_string())) {
+        Err(GameError::Placing(PlacingError::InvalidPosition)) => println!("ERROR: Invalid position - {}!", position),
+        Err(GameError::Placing(PlacingError::PlaceAtOccupied)) => println!("ERROR: Position {} is already occupied!", position),
+        Err(GameError::NothingToPlace) => println!("ERROR: You have no pieces left to place!"),
+        Err(error) => println!("ERROR: {:?}", error),
+        Ok(()) => return true
+    }
+    false
+}
+
+// Requests the coordinates of two positions, reads a single line from the standard input and checks if it is of the appropriate length.
+// If yes, applies Action::Move with both positions (the game itself checks whether the current player can "fly" their pieces).
+// Handles the game's errors and prints an approriate message if there is one.
+// If the movement is successful, returns the second position. If a command is given instead, returns None.
+fn move_piece<R: BufRead>(game: &mut Game, mut input: R) -> Option<String> {
+    if game.flying() {
+        println!("{} player, MOVE your piece to any position ('fly'):", game.current());
+    } else {
+        println!("{} player, MOVE your piece to an adjacent position:", game.current());
+    }
+
+    loop {
+        let mut positions = String::new();
+        match input.read_line(&mut positions) {
+            Err(error) => println!("ERROR: input error - {}", error),
+            Ok(_) if command(game, positions.trim_end()) => return None,
+            Ok(6) => { // letter + digit + letter + digit + \r + \n
+                let trimmed_positions = positions.trim_end();
+                let start = &trimmed_positions[..2];
+                let end = &trimmed_positions[2..];
+
+                if apply_move(game, start, end) {
+                    return Some(end.to_string());
+                }
+            }
+            Ok(_) => println!("ERROR: Invalid input - must be 4 symbols (e.g. a7a4)!"),
+        }
+    }
+}
+
+// Applies Action::Move with the given positions, printing an appropriate message if there is an error - returns true if it succeeds.
+fn apply_move(game: &mut Game, start: &str, end: &str) -> bool {
+    match game.apply(Action::Move(start.to_string(), end.to_string())) {
+        Err(GameError::Moving(MovingError::InvalidMoveFrom))    => println!("ERROR: Invalid first position - {}!", start),
+        Err(GameError::Moving(MovingError::InvalidMoveTo))      => println!("ERROR: Invalid second position - {}!", end),
+        Err(GameError::Moving(MovingError::MoveToSame))         => println!("ERROR: The two positions are identical!"),
+        Err(GameError::Moving(MovingError::MoveFromEmpty))      => println!("ERROR: The starting position {} doesn't have a piece to move!", start),
+        Err(GameError::Moving(MovingError::MoveFromWrongColor)) => println!("ERROR: The starting position {} isn't occupied by you!", start),
+        Err(GameError::Moving(MovingError::MoveToOccupied))     => println!("ERROR: The target position {} is already occupied!", end),
+        Err(GameError::Moving(MovingError::NotAdjacent))        => println!("ERROR: Can't move from {} to {}!", start, end),
+        Err(error) => println!("ERROR: {:?}", error),
+        Ok(()) => return true
+    }
+    false
+}
+
+// Requests either the coordinates of a single position (to place a piece there) or of two positions (to move a piece between them),
+// for variants where placing and moving are interleaved. Reads a single line and applies Action::Place or Action::Move depending on its length.
+// If the placement or movement is successful, returns the position the piece ended up at. If a command is given instead, returns None.
+fn place_or_move_piece<R: BufRead>(game: &mut Game, mut input: R) -> Option<String> {
+    println!("{} player, PLACE a piece (e.g. a7) or MOVE one of yours (e.g. a7a4):", game.current());
+
+    loop {
+        let mut line = String::new();
+        match input.read_line(&mut line) {
+            Err(error) => println!("ERROR: input error - {}", error),
+            Ok(_) => {
+                let trimmed = line.trim_end();
+                if command(game, trimmed) {
+                    return None;
+                }
+
+                match trimmed.len() {
+                    2 if apply_place(game, trimmed) => return Some(trimmed.to_string()),
+                    4 if trimmed.is_char_boundary(2) && apply_move(game, &trimmed[..2], &trimmed[2..]) => return Some(trimmed[2..].to_string()),
+                    2 | 4 => {}
+                    _ => println!("ERROR: Invalid input - must be 2 or 4 symbols (e.g. a7 or a7a4)!")
+                }
+            }
+        }
+    }
+}
+
+// Requests the coordinates of a single position, reads them from the standard input and applies Action::Remove with said position.
+// Handles the game's errors and prints an approriate message if there is one.
+// If the removal is successful, returns the position. If a command is given instead, returns None.
+fn remove_piece<R: BufRead>(game: &mut Game, mut input: R) -> Option<String> {
+    println!("{} player, REMOVE opponent's piece:", game.current());
+
+    loop {
+        let mut position = String::new();
+        match input.read_line(&mut position) {
+            Err(error) => println!("ERROR: input error - {}", error),
+            Ok(_) => {
+                let trimmed_position = position.trim_end();
+                if command(game, trimmed_position) {
+                    return None;
+                }
+
+                match game.apply(Action::Remove(trimmed_position.to_string())) {
+                    Err(GameError::Removing(RemovingError::InvalidPosition))      => println!("ERROR: Invalid position - {}!", trimmed_position),
+                    Err(GameError::Removing(RemovingError::RemoveFromEmpty))      => println!("ERROR: Cannot remove from empty position {}!", trimmed_position),
+                    Err(GameError::Removing(RemovingError::RemoveFromWrongColor)) => println!("ERROR: Cannot remove your own pieces (from position {})!", trimmed_position),
+                    Err(GameError::Removing(RemovingError::RemoveFromMill))       => println!("ERROR: Cannot remove from opponent's mills (from position {})!", trimmed_position),
+                    Err(error) => println!("ERROR: {:?}", error),
+                    Ok(()) => return Some(trimmed_position.to_string())
+                }
+            }
+        }
+    }
+}
+
+// Requests information about whether the player of the given color is a human or the computer - returns true for the computer.
+fn read_computer(color: Color) -> bool {
+    loop {
+        println!("Should the {} player be a human or the computer? (human or computer)", color);
+
+        let mut input = String::new();
+        match io::stdin().read_line(&mut input) {
+            Err(error) => println!("ERROR: input error - {}", error),
+            Ok(_) => {
+                match input.trim_end() {
+                    "human" => return false,
+                    "computer" => return true,
+                    _ => println!("ERROR: Invalid input!")
+                }
+            }
+        }
+    }
+}
+
+// Requests the number of moves the computer should look ahead - higher numbers make it play better, but slower.
+fn read_depth() -> u32 {
+    loop {
+        println!("How many moves ahead should the computer look? (1 to 6)");
+
+        let mut input = String::new();
+        match io::stdin().read_line(&mut input) {
+            Err(error) => println!("ERROR: input error - {}", error),
+            Ok(_) => {
+                match input.trim_end().parse::<u32>() {
+                    Ok(depth) if (1..=6).contains(&depth) => return depth,
+                    _ => println!("ERROR: Invalid input!")
+                }
+            }
+        }
+    }
+}
+
+// Requests information about which player should move first.
+fn read_first(game: &mut Game) {
+    loop {
+        println!("Who should move first? (white or black)?");
+
+        let mut input = String::new();
+        match io::stdin().read_line(&mut input) {
+            Err(error) => println!("ERROR: input error - {}", error),
+            Ok(_) => {
+                let trimmed = input.trim_end();
+
+                if trimmed == "white" {
+                    game.set_current(Color::White);
+                    break;
+                } else if trimmed == "black" {
+                    game.set_current(Color::Black);
+                    break;
+                } else {
+                    println!("ERROR: Invalid input!");
+                }
+            }
+        }
+    }
+}
+
+// Requests information about which player should move first (unless the game was loaded), and which players are played by the computer.
+// After that, loops while the game can continue (both players have enough pieces and can move at least one of them), with each iteration
+// calling either place() or move() depending on the phase of the game. If a mill is formed, calls remove().
+// If a command is given during any of those, the state of the game is checked again before continuing.
+// On the computer's turns, its move is chosen by an alpha-beta search instead.
+// After the looping condition becomes false, prints the outcome of the game, 
+// describing who won and by what cause in the case of victory, or that the outcome is a draw.
+pub fn game_loop(game: &mut Game, loaded: bool, tablebase: Option<Arc<Tablebase>>, evaluation: Evaluation) {
+    if !loaded {
+        read_first(game);
+    }
+
+    let computer = [read_computer(Color::White), read_computer(Color::Black)];
+    let is_computer = |color: Color| match color {
+        Color::White => computer[0],
+        Color::Black => computer[1]
+    };
+    let mut search = AlphaBeta::new(if computer[0] || computer[1] { read_depth() } else { 0 }).with_evaluation(evaluation);
+    if let Some(tablebase) = tablebase {
+        search = search.with_tablebase(tablebase);
+    }
+
+    print!("\nINSTRUCTIONS:\n\
+            At the start of every turn, the board is printed, with the occupied positions marked by ○ for white pieces and ● for black pieces.\n\
+            Depending on the phase of the game, the players are asked to input coordinates of positions:\n\
+            - during the placement phase, input the coordinates of a single position (e.g. 'a7') to place a piece there. The position should be unoccupied.\n\
+            - during the movement phase, input the coordinates of two positions (e.g. 'a7a4') to move a piece from the first to the second. \
+            The first position should have a piece of your color, and the second should be adjacent to it and unoccupied.\n\
+            If you have only 3 pieces left however, you can move to non-adjacent positions.\n\
+            \n\
+            If a mill is formed at any point, a message will be printed and you will need to input the coordinates of a single position, \
+            from which to remove a piece belonging to your opponent.\n\
+            Instead of coordinates, you can also input 'undo' to take back the last move, 'redo' to play an undone move again, or 'resign' to give up.\n\
+            You can also input 'save <file>' to save the game to a file, or 'load <file>' to continue a game saved earlier.\n\
+            In the case of invalid input (positions that don't exist, placing on already occupied positions, moving your opponent's pieces, etc.) \
+            an appropriate error message is printed and the turn is repeated (until correct input is given).
+            \n"
+    );
+
+    while !game.is_over() {
+        print!("{}", game);
+
+        if is_computer(game.current()) {
+            let m = search.best_move(game).expect("ERROR: the computer has no legal moves");
+            println!("{} player (computer) plays {}", game.current(), m);
+            game.make_move(&m).expect("ERROR: the computer chose an illegal move");
+            continue;
+        }
+
+        let length = game.history().len();
+        let result = match game.phase() {
+            Phase::Placing if game.rules().interleaved => match game.pieces(game.current()).unplaced {
+                0 => move_piece(game, io::stdin().lock()),
+                _ => place_or_move_piece(game, io::stdin().lock())
+            },
+            Phase::Placing => place_piece(game, io::stdin().lock()),
+            Phase::Moving  => move_piece(game, io::stdin().lock())
+        };
+
+        // when playing against the computer, undo takes back its move as well, so that it's the human's turn again
+        if result.is_none() && game.history().len() < length && is_computer(game.current()) {
+            let _ = game.undo();
+        }
+
+        // a move which forms two mills at once can require two removals
+        while game.removal_pending() {
+            print!("{}", game);
+            println!("{} player FORMED A MILL!", game.current());
+
+            remove_piece(game, io::stdin().lock());
+        }
+    }
+
+    print!("{}", game);
+    if let Some(result) = game.outcome() {
+        println!("{}", result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solution::State;
+
+    #[test]
+    fn test_place() {
+        let mut game = Game::default();
+
+        assert_eq!(place_piece(&mut game, &b"a7\r\n"[..]), Some("a7".to_string()));
+        assert_eq!(game.board().state("a7").unwrap(), State::Occupied(Color::White));
+
+        assert_eq!(place_piece(&mut game, &b"a7\r\na1\r\n"[..]), Some("a1".to_string()));
+        assert_eq!(game.board().state("a1").unwrap(), State::Occupied(Color::Black));
+    }
+    #[test]
+    fn test_move() {
+        let mut game = Game::default();
+
+        let placements = ["a7", "d7", "g7", "d6", "b6", "d1",
+                          "f6", "a4", "c5", "g4", "e5", "b4",
+                          "a1", "f4", "g1", "c3", "d2", "e3"];
+        for position in placements.iter() {
+            place_piece(&mut game, position.as_bytes());
+        }
+
+        assert_eq!(move_piece(&mut game, &b"d2\r\nd2e4\r\nd2b2\r\n"[..]), Some("b2".to_string()));
+        assert_eq!(game.board().state("d2").unwrap(), State::Empty);
+        assert_eq!(game.board().state("b2").unwrap(), State::Occupied(Color::White));
+    }
+    #[test]
+    fn test_remove() {
+        let mut game = Game::default();
+
+        assert_eq!(place_piece(&mut game, &b"a7\r\n"[..]), Some("a7".to_string()));
+        assert_eq!(place_piece(&mut game, &b"a1\r\n"[..]), Some("a1".to_string()));
+        assert_eq!(place_piece(&mut game, &b"d7\r\n"[..]), Some("d7".to_string()));
+        assert_eq!(place_piece(&mut game, &b"d1\r\n"[..]), Some("d1".to_string()));
+        assert_eq!(place_piece(&mut game, &b"g7\r\n"[..]), Some("g7".to_string()));
+
+        assert!(game.removal_pending());
+        assert_eq!(remove_piece(&mut game, &b"a7\r\nd1\r\n"[..]), Some("d1".to_string()));
+        assert_eq!(game.board().state("d1").unwrap(), State::Empty);
+        assert_eq!(game.current(), Color::Black);
+    }
+
+    #[test]
+    fn test_commands() {
+        let mut game = Game::default();
+
+        assert_eq!(place_piece(&mut game, &b"undo\r\n"[..]), None);
+        assert_eq!(place_piece(&mut game, &b"a7\r\n"[..]), Some("a7".to_string()));
+        assert_eq!(place_piece(&mut game, &b"undo\r\n"[..]), None);
+        assert_eq!(game.board().state("a7").unwrap(), State::Empty);
+        assert_eq!(game.current(), Color::White);
+
+        assert_eq!(place_piece(&mut game, &b"redo\r\n"[..]), None);
+        assert_eq!(game.board().state("a7").unwrap(), State::Occupied(Color::White));
+        assert_eq!(game.current(), Color::Black);
+
+        assert_eq!(place_piece(&mut game, &b"a1\r\n"[..]), Some("a1".to_string()));
+        assert_eq!(place_piece(&mut game, &b"d7\r\n"[..]), Some("d7".to_string()));
+        assert_eq!(place_piece(&mut game, &b"d1\r\n"[..]), Some("d1".to_string()));
+        assert_eq!(place_piece(&mut game, &b"g7\r\n"[..]), Some("g7".to_string()));
+        assert_eq!(remove_piece(&mut game, &b"undo\r\n"[..]), None);
+        assert!(!game.removal_pending());
+        assert_eq!(game.board().state("g7").unwrap(), State::Empty);
+    }
+    #[test]
+    fn test_save_load() {
+        let path = std::env::temp_dir().join(format!("morris-test-{}.txt", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let mut game = Game::default();
+        assert_eq!(place_piece(&mut game, &b"a7\r\n"[..]), Some("a7".to_string()));
+        assert_eq!(place_piece(&mut game, &b"a1\r\n"[..]), Some("a1".to_string()));
+        assert_eq!(place_piece(&mut game, format!("save {}\r\n", path).as_bytes()), None);
+        assert_eq!(place_piece(&mut game, &b"d7\r\n"[..]), Some("d7".to_string()));
+
+        assert_eq!(place_piece(&mut game, format!("load {}\r\n", path).as_bytes()), None);
+        assert_eq!(game.history().len(), 2);
+        assert_eq!(game.board().state("d7").unwrap(), State::Empty);
+        assert_eq!(game.current(), Color::White);
+
+        assert_eq!(place_piece(&mut game, &b"load /nonexistent/game.txt\r\n"[..]), None);
+        assert_eq!(game.history().len(), 2);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_place_or_move() {
+        let mut game = Game::new(Rules::lasker_morris());
+
+        assert_eq!(place_or_move_piece(&mut game, &b"a7\r\n"[..]), Some("a7".to_string()));
+        assert_eq!(place_or_move_piece(&mut game, &b"a1\r\n"[..]), Some("a1".to_string()));
+        assert_eq!(place_or_move_piece(&mut game, &b"a7\r\na7a\r\na1a4\r\na7a4\r\n"[..]), Some("a4".to_string()));
+        assert_eq!(game.board().state("a4").unwrap(), State::Occupied(Color::White));
+        assert_eq!(game.pieces(Color::White).unplaced, 9);
+    }
+
+    #[test]
+    fn test_load_layout() {
+        let layout = load_layout(concat!(env!("CARGO_MANIFEST_DIR"), "/boards/six_mens_morris.txt")).unwrap();
+        assert_eq!(layout.len(), 16);
+        assert!(load_layout("/nonexistent/board.txt").is_none());
+
+        let mut game = Game::new(Rules { layout: std::sync::Arc::new(layout), pieces: 6, ..Rules::default() });
+        assert_eq!(place_piece(&mut game, &b"a5\r\n"[..]), Some("a5".to_string()));
+        assert_eq!(place_piece(&mut game, &b"d7\r\nb4\r\n"[..]), Some("b4".to_string()));
+    }
+
+    #[test]
+    fn test_load_evaluation() {
+        let evaluation = load_evaluation(concat!(env!("CARGO_MANIFEST_DIR"), "/evaluation/default.txt")).unwrap();
+        assert_eq!(evaluation, Evaluation::default());
+        assert!(load_evaluation("/nonexistent/evaluation.txt").is_none());
+        assert!(load_evaluation(concat!(env!("CARGO_MANIFEST_DIR"), "/boards/six_mens_morris.txt")).is_none());
+    }
+
+    #[test]
+    fn test_load_tablebase() {
+        let rules = Rules::three_mens_morris();
+        let path = std::env::temp_dir().join(format!("tablebase-{}.bin", std::process::id()));
+        let path = path.to_str().unwrap();
+        fs::write(path, Tablebase::generate(&rules, 3, 3).to_bytes()).unwrap();
+
+        assert_eq!(load_tablebase(path, &rules).unwrap().len(3, 3), Tablebase::generate(&rules, 3, 3).len(3, 3));
+        assert!(load_tablebase(path, &Rules::default()).is_none());
+        assert!(load_tablebase("/nonexistent/tablebase.bin", &rules).is_none());
+        fs::remove_file(path).unwrap();
+    }
+}
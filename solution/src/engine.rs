@@ -0,0 +1,342 @@
+use crate::game::Game;
+use crate::ai::{AlphaBeta, Info, WIN, DECIDED, MAX_DEPTH};
+use crate::rules::Rules;
+use crate::tablebase::Tablebase;
+use crate::transposition::{TranspositionTable, DEFAULT_SIZE, MAX_SIZE};
+use crate::evaluation::Evaluation;
+
+use std::io::{BufRead, Write};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+// the depth searched by "go" when neither a depth nor a time is given
+pub const DEFAULT_DEPTH: u32 = 4;
+
+// An engine speaking a line-based text protocol (modelled on the UCI protocol of chess engines), so that it can be driven by a GUI
+// or played against other engines. It reads one command per line:
+// - "newgame" - starts a new game
+// - "position startpos|<notation> [moves <move>...]" - sets up the position from the start or from Game.to_notation(), then plays the moves
+// - "go [depth <plies>] [movetime <ms>]" - searches the position in the background, deepening until the depth or the time is reached.
+//   Every iteration prints "info depth <plies> score <score> nodes <count> time <ms> pv <moves>", and the search ends with "bestmove <move>"
+//   ("bestmove none" when there's no legal move). The score is "cp <value>" from the evaluation (100 for a piece),
+//   or "win <plies>" / "loss <plies>" when the end of the game was found
+// - "stop" - stops the search, which then prints the best move found so far
+// - "setoption hash <megabytes>" - sets the size of the transposition table (up to transposition::MAX_SIZE), which is kept between searches
+//   (and cleared by "newgame")
+// - "isready" - answered with "readyok"
+// - "quit" - stops the engine
+// Errors are reported as "info string ERROR: ..." and leave the position unchanged.
+pub struct Engine<W: Write + Send + 'static> {
+    rules: Rules,
+    game: Game,
+    tablebase: Option<Arc<Tablebase>>,
+    evaluation: Evaluation,
+    table: Option<TranspositionTable>, // None while a search is using it
+    output: Arc<Mutex<W>>,
+    search: Option<(Arc<AtomicBool>, JoinHandle<TranspositionTable>)> // the stop flag and the thread of the running search, which returns the table
+}
+
+impl<W: Write + Send + 'static> Engine<W> {
+    pub fn new(rules: Rules, tablebase: Option<Arc<Tablebase>>, output: W) -> Self {
+        Self {
+            game: Game::new(rules.clone()), rules, tablebase, evaluation: Evaluation::default(), table: Some(TranspositionTable::new(DEFAULT_SIZE)),
+            output: Arc::new(Mutex::new(output)), search: None
+        }
+    }
+
+    // Returns the same engine, scoring positions by the given evaluation.
+    pub fn with_evaluation(self, evaluation: Evaluation) -> Self {
+        Self { evaluation, ..self }
+    }
+
+    // Reads and executes commands until "quit" or the end of the input, waiting for the last search to finish.
+    pub fn run<R: BufRead>(&mut self, input: R) {
+        for line in input.lines() {
+            match line {
+                Ok(line) => if !self.command(&line) {
+                    break;
+                },
+                Err(error) => {
+                    send(&self.output, &format!("info string ERROR: input error - {}", error));
+                    break;
+                }
+            }
+        }
+        self.wait();
+    }
+
+    // Executes a single command - returns false if it was "quit".
+    pub fn command(&mut self, line: &str) -> bool {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let result = match words.split_first() {
+            None => Ok(()),
+            Some((&"newgame", _)) => {
+                self.stop();
+                self.game = Game::new(self.rules.clone());
+                self.table_mut().clear();
+                Ok(())
+            }
+            Some((&"position", args)) => {
+                self.stop();
+                self.position(args)
+            }
+            Some((&"go", args)) => {
+                self.stop();
+                self.go(args)
+            }
+            Some((&"stop", _)) => {
+                self.stop();
+                Ok(())
+            }
+            Some((&"setoption", args)) => {
+                self.stop();
+                self.set_option(args)
+            }
+            Some((&"isready", _)) => {
+                send(&self.output, "readyok");
+                Ok(())
+            }
+            Some((&"quit", _)) => {
+                self.stop();
+                return false;
+            }
+            Some((command, _)) => Err(format!("Unknown command - {}", command))
+        };
+
+        if let Err(message) = result {
+            send(&self.output, &format!("info string ERROR: {}", message));
+        }
+        true
+    }
+
+    // the current position, as set up by the last "newgame" or "position" command
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    fn position(&mut self, args: &[&str]) -> Result<(), String> {
+        let (mut game, moves) = match args.split_first() {
+            Some((&"startpos", moves)) => (Game::new(self.rules.clone()), moves),
+            Some(_) => {
+                let end = args.iter().position(|arg| *arg == "moves").unwrap_or(args.len());
+                let game = Game::from_notation(&args[..end].join(" "), self.rules.clone()).map_err(|error| format!("Invalid position - {:?}", error))?;
+                (game, &args[end..])
+            }
+            None => return Err("Missing position".to_string())
+        };
+
+        match moves.split_first() {
+            None => {}
+            Some((&"moves", moves)) => for text in moves {
+                let m = game.parse_move(text).map_err(|error| format!("Invalid move {} - {:?}", text, error))?;
+                game.make_move(&m).map_err(|error| format!("Illegal move {} - {:?}", text, error))?;
+            },
+            Some((word, _)) => return Err(format!("Expected moves, found {}", word))
+        }
+
+        self.game = game;
+        Ok(())
+    }
+
+    fn set_option(&mut self, args: &[&str]) -> Result<(), String> {
+        match args {
+            [name, value] if *name == "hash" => {
+                let megabytes = value.parse().ok().filter(|megabytes| *megabytes <= MAX_SIZE).ok_or(format!("Invalid hash size - {}", value))?;
+                self.table = Some(TranspositionTable::new(megabytes));
+                Ok(())
+            }
+            _ => Err(format!("Unknown option - {}", args.join(" ")))
+        }
+    }
+
+    fn go(&mut self, args: &[&str]) -> Result<(), String> {
+        let mut depth = None;
+        let mut movetime = None;
+        for pair in args.chunks(2) {
+            match pair {
+                [name, value] if *name == "depth" => depth = Some(value.parse().ok().filter(|depth| *depth > 0).ok_or(format!("Invalid depth - {}", value))?),
+                [name, value] if *name == "movetime" => movetime = Some(value.parse().map_err(|_| format!("Invalid movetime - {}", value))?),
+                _ => return Err(format!("Invalid search limits - {}", pair.join(" ")))
+            }
+        }
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let output = self.output.clone();
+        let mut search = AlphaBeta::new(depth.unwrap_or(if movetime.is_some() { MAX_DEPTH } else { DEFAULT_DEPTH }))
+            .with_evaluation(self.evaluation.clone())
+            .with_table(self.table.take().expect("ERROR: the transposition table is in use"))
+            .with_stop(stop.clone())
+            .with_info(move |info: &Info| send(&output, &info_line(info)));
+        if let Some(milliseconds) = movetime {
+            search = search.with_time_limit(Duration::from_millis(milliseconds));
+        }
+        if let Some(tablebase) = &self.tablebase {
+            search = search.with_tablebase(tablebase.clone());
+        }
+
+        let game = self.game.clone();
+        let output = self.output.clone();
+        let handle = thread::spawn(move || {
+            match search.best_move(&game) {
+                Some(m) => send(&output, &format!("bestmove {}", m)),
+                None => send(&output, "bestmove none")
+            }
+            search.into_table()
+        });
+
+        self.search = Some((stop, handle));
+        Ok(())
+    }
+
+    // Stops the running search (if there is one) and waits for it to print its move.
+    fn stop(&mut self) {
+        if let Some((stop, _)) = &self.search {
+            stop.store(true, Ordering::Relaxed);
+        }
+        self.wait();
+    }
+
+    // Waits for the running search (if there is one) to finish.
+    fn wait(&mut self) {
+        if let Some((_, handle)) = self.search.take() {
+            self.table = Some(handle.join().expect("ERROR: the search failed"));
+        }
+    }
+
+    fn table_mut(&mut self) -> &mut TranspositionTable {
+        self.table.as_mut().expect("ERROR: the transposition table is in use")
+    }
+}
+
+// the line reporting an iteration of the search
+fn info_line(info: &Info) -> String {
+    let pv: Vec<String> = info.pv.iter().map(|m| m.to_string()).collect();
+    format!("info depth {} score {} nodes {} time {} pv {}", info.depth, score(info.score), info.nodes, info.time.as_millis(), pv.join(" "))
+}
+
+// the score as written in "info" lines
+fn score(score: i32) -> String {
+    if score.abs() > DECIDED {
+        format!("{} {}", if score > 0 { "win" } else { "loss" }, WIN - score.abs())
+    } else {
+        format!("cp {}", score)
+    }
+}
+
+fn send<W: Write>(output: &Mutex<W>, line: &str) {
+    let mut output = output.lock().expect("ERROR: output lock poisoned");
+    writeln!(output, "{}", line).expect("ERROR: output error");
+    output.flush().expect("ERROR: output error");
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::Color;
+
+    // a writer which can still be read after the engine is done with it
+    struct SharedOutput(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedOutput {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    // runs the commands, returning the output
+    fn run(rules: Rules, commands: &str) -> Vec<String> {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let mut engine = Engine::new(rules, None, SharedOutput(output.clone()));
+        engine.run(commands.as_bytes());
+
+        let output = output.lock().unwrap();
+        String::from_utf8(output.clone()).unwrap().lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_go() {
+        let output = run(Rules::default(), "isready\nposition startpos moves a7 b6 d7 d6\ngo depth 2\n");
+        assert_eq!(output[0], "readyok");
+        assert!(output[1].starts_with("info depth 1 score cp "), "{}", output[1]);
+        assert!(output[2].starts_with("info depth 2 score cp "), "{}", output[2]);
+        assert!(output[2].contains(" nodes ") && output[2].contains(" pv g7x"));
+        assert!(output[3].starts_with("bestmove g7x"), "{}", output[3]);
+        assert_eq!(output.len(), 4);
+
+        // a won game is scored by the number of plies to the win - which ends the search, as it can't be improved on
+        let output = run(Rules::three_mens_morris(), "position WW1/BWB/B11 w m 0 0\ngo depth 4\n");
+        assert!(output[0].starts_with("info depth 1 score win 1 nodes "), "{}", output[0]);
+        assert!(output[1].starts_with("bestmove b2-c3x"), "{}", output[1]);
+
+        // in a game which has ended, there's no move to play
+        let output = run(Rules::three_mens_morris(), "position WWW/BB1/111 b m 0 0\ngo\n");
+        assert_eq!(output, vec!["bestmove none".to_string()]);
+    }
+
+    #[test]
+    fn test_position() {
+        let mut engine = Engine::new(Rules::default(), None, Vec::new());
+        assert!(engine.command("position startpos moves a7 b6"));
+        assert_eq!(engine.game().history().len(), 2);
+
+        let notation = engine.game().to_notation();
+        assert!(engine.command(&format!("position {} moves d7", notation)));
+        assert_eq!(engine.game().history().len(), 1);
+        assert_eq!(engine.game().board().occupied(Color::White).count_ones(), 2);
+
+        // the transposition table is kept between searches, until a new game starts
+        assert!(engine.command("go depth 2"));
+        engine.wait();
+        assert!(!engine.table_mut().is_empty());
+        assert!(engine.command("newgame"));
+        assert_eq!(engine.game().history().len(), 0);
+        assert!(engine.table_mut().is_empty());
+
+        assert!(engine.command("setoption hash 1"));
+        assert!(engine.table_mut().capacity() < TranspositionTable::new(DEFAULT_SIZE).capacity());
+
+        // errors leave the position unchanged
+        engine.command("position startpos moves a7 a7");
+        engine.command("position nonsense");
+        engine.command("position startpos d7");
+        engine.command("go depth x");
+        engine.command("fly");
+        engine.command("setoption hash many");
+        engine.command("setoption hash 100000");
+        engine.command("setoption hash 18446744073709551615");
+        assert_eq!(engine.game().history().len(), 0);
+        assert_eq!(engine.table_mut().capacity(), TranspositionTable::new(1).capacity());
+        assert!(engine.command("isready"));
+        assert!(!engine.command("quit"));
+
+        let output = String::from_utf8(engine.output.lock().unwrap().clone()).unwrap();
+        let errors: Vec<&str> = output.lines().filter(|line| line.starts_with("info string ERROR: ")).collect();
+        assert_eq!(errors.len(), 8);
+        assert!(output.lines().any(|line| line == "readyok"));
+    }
+
+    #[test]
+    fn test_stop() {
+        // a deep search is cut short by movetime, or by stop - both still answer with a legal move
+        let output = run(Rules::default(), "go depth 30 movetime 50\n");
+        assert!(output.last().unwrap().starts_with("bestmove "));
+        assert!(output.len() >= 2);
+
+        let output = run(Rules::default(), "go depth 30\nstop\nquit\n");
+        let m = output.last().unwrap().strip_prefix("bestmove ").unwrap();
+        assert!(Game::default().legal_moves().iter().any(|legal| legal.to_string() == m));
+    }
+
+    #[test]
+    fn test_score() {
+        assert_eq!(score(WIN - 3), "win 3");
+        assert_eq!(score(-(WIN - 4)), "loss 4");
+        assert_eq!(score(-150), "cp -150");
+    }
+}
@@ -12,6 +12,22 @@ impl Color {
             Self::Black => Self::White,
         }
     }
+
+    // The plain name of the color, as used in saved games.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::White => "white",
+            Self::Black => "black",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "white" => Some(Self::White),
+            "black" => Some(Self::Black),
+            _ => None
+        }
+    }
 }
 
 use std::fmt::{self, Display, Formatter};
@@ -25,7 +41,7 @@ impl Display for Color {
 }
 
 // the two main phases of the game - placing pieces (until each player places all 9 of theirs) and moving the already placed pieces
-#[derive(PartialEq, Eq, Clone, Copy)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub enum Phase {
     Placing,
     Moving
@@ -38,11 +54,76 @@ pub enum State {
     Occupied(Color)
 }
 
+impl Display for State {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            State::Empty                  => write!(f, "·"),
+            State::Occupied(Color::White) => write!(f, "○"),
+            State::Occupied(Color::Black) => write!(f, "●"),
+        }
+    }
+}
+
 // one of the four directions on the board
 #[derive(PartialEq, Eq, Clone, Copy)]
 pub enum Direction {
     Above,
     Left,
     Right,
-    Below
+    Below,
+
+    // diagonals, only used by some boards (e.g. twelve men's morris)
+    AboveLeft,
+    AboveRight,
+    BelowLeft,
+    BelowRight
+}
+
+// the reason a player won the game
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum WinReason {
+    TooFewPieces, // the opponent has less than 3 pieces
+    Blocked,      // the opponent cannot move any of their pieces
+    Resignation,  // the opponent resigned
+    Timeout,      // the opponent ran out of time
+    IllegalMove   // the opponent attempted an illegal move (when the game is played by Game.play())
+}
+
+// the reason a game ended in a draw
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum DrawReason {
+    BothBlocked,        // neither player can move their pieces
+    Repetition(u32),    // the same position occurred the given number of times
+    NoMills(u32)        // the given number of moves were made without forming a mill
+}
+
+// the outcome of a finished game
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum GameResult {
+    Win { winner: Color, reason: WinReason },
+    Draw { reason: DrawReason }
+}
+
+impl Display for GameResult {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            GameResult::Win { winner, reason } => {
+                let (winner, loser) = match winner {
+                    Color::White => ("WHITE", "black"),
+                    Color::Black => ("BLACK", "white")
+                };
+
+                match reason {
+                    WinReason::TooFewPieces => write!(f, "VICTORY for {} player - {} player has less than 3 pieces!", winner, loser),
+                    WinReason::Blocked      => write!(f, "VICTORY for {} player - {} player cannot move their pieces!", winner, loser),
+                    WinReason::Resignation  => write!(f, "VICTORY for {} player - {} player resigned!", winner, loser),
+                    WinReason::Timeout      => write!(f, "VICTORY for {} player - {} player ran out of time!", winner, loser),
+                    WinReason::IllegalMove  => write!(f, "VICTORY for {} player - {} player made an illegal move!", winner, loser),
+                }
+            }
+            GameResult::Draw { reason: DrawReason::BothBlocked }       => write!(f, "DRAW - neither player can move their pieces!"),
+            GameResult::Draw { reason: DrawReason::Repetition(count) } => write!(f, "DRAW - the same position occurred {} times!", count),
+            GameResult::Draw { reason: DrawReason::NoMills(count) }    => write!(f, "DRAW - no mill was formed in the last {} moves!", count)
+        }
+    }
 }
\ No newline at end of file
@@ -27,3 +27,96 @@ pub enum RemovingError {
     RemoveFromWrongColor,
     RemoveFromMill
 }
+
+// errors that can occur when applying an action to a game
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GameError {
+    Placing(PlacingError),
+    Moving(MovingError),
+    Removing(RemovingError),
+
+    WrongPhase,         // placing during the moving phase or vice versa
+    RemovalExpected,    // a mill was formed, so the next action has to be a removal
+    RemovalUnexpected,  // a removal was attempted without forming a mill
+    FlyingNotAllowed,   // flying was attempted by a player with more than 3 pieces
+    NothingToPlace,     // placing was attempted by a player who has placed all of their pieces (possible when placing and moving are interleaved)
+    NothingToUndo,
+    NothingToRedo,
+    GameOver
+}
+
+impl From<PlacingError> for GameError {
+    fn from(error: PlacingError) -> Self {
+        GameError::Placing(error)
+    }
+}
+impl From<MovingError> for GameError {
+    fn from(error: MovingError) -> Self {
+        GameError::Moving(error)
+    }
+}
+impl From<RemovingError> for GameError {
+    fn from(error: RemovingError) -> Self {
+        GameError::Removing(error)
+    }
+}
+
+// errors that can occur when parsing a position or a move from text
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseMoveError {
+    InvalidPosition,
+    InvalidFormat
+}
+
+// errors that can occur when loading a saved game
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LoadError {
+    MissingFirst,                       // the first line doesn't say which player moved first
+    InvalidMove(usize),                 // the line with the given number isn't a valid move
+    IllegalMove(usize, GameError),      // the move on the line with the given number can't be played in the game so far
+    InvalidResult(usize)                // the line with the given number is neither a move nor a valid result
+}
+
+// errors that can occur when reading a position from its notation (see Game.from_notation())
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NotationError {
+    InvalidFormat,      // not five fields separated by spaces
+    InvalidBoard,       // wrong number of rows, a row of the wrong length or an unknown symbol
+    InvalidColor,       // the side to move isn't 'w' or 'b'
+    InvalidPhase,       // the phase isn't 'p' or 'm'
+    InvalidCount,       // an unplaced count isn't a number
+    TooManyPieces,      // a player has more pieces than the rules allow (placed and unplaced)
+    UnplacedMismatch,   // the unplaced counts can't result from the players placing in turns with the given side to move
+    PhaseMismatch       // the phase doesn't agree with the unplaced counts
+}
+
+// errors that can occur when reading a board from its description (see Layout.from_description())
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LayoutError {
+    InvalidLine(usize),         // the line with the given number isn't a section header, a point with its neighbours or a mill
+    InvalidPoint(usize),        // a position on the line isn't a letter followed by a number
+    DuplicatePoint(usize),      // the point on the line was already listed
+    UnknownPoint(usize),        // a neighbour or a mill on the line refers to a point which isn't listed
+    InvalidEdge(usize),         // a point is its own neighbour, or two of its neighbours lie in the same direction
+    AsymmetricEdge(usize),      // a neighbour on the line doesn't list the point as its own neighbour
+    InvalidMill(usize),         // the mill on the line isn't three different points
+    NonCollinearMill(usize),    // the points of the mill on the line don't lie on a straight line
+    TooManyPoints,              // more than 32 points
+    InvalidDiagram              // the diagram doesn't have a '#' for every point
+}
+
+// errors that can occur when reading the weights of an evaluation (see Evaluation.from_config())
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EvaluationError {
+    InvalidLine(usize),     // the line with the given number isn't a section header or a feature with its weight (within a section)
+    UnknownStage(usize),    // the section on the line isn't one of the stages
+    UnknownFeature(usize),  // the feature on the line isn't one of the features
+    InvalidWeight(usize)    // the weight on the line isn't a whole number
+}
+
+// errors that can occur when reading a tablebase (see Tablebase.from_bytes())
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TablebaseError {
+    InvalidFormat,  // not a tablebase, or a truncated one
+    RulesMismatch   // the tablebase was generated for a different board or different rules
+}
@@ -0,0 +1,245 @@
+use crate::game::Game;
+use crate::enums::{Color, Phase};
+use crate::errors::EvaluationError;
+use crate::ai::DECIDED;
+
+// a property of a player's position, counted by Evaluation.features()
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Feature {
+    Material,               // the pieces left, placed or not
+    ClosedMills,            // the mills formed by the player's pieces
+    BlockedPieces,          // the pieces which can't move (none for a player who flies)
+    TwoPieceConfigurations, // the mills with two of the player's pieces and an empty position, which threaten to be formed
+    DoubleMills,            // the pairs of the player's mills which share a piece
+    Mobility                // the moves of the player's pieces (to any empty position for a player who flies)
+}
+
+impl Feature {
+    pub const ALL: [Feature; 6] = [Feature::Material, Feature::ClosedMills, Feature::BlockedPieces,
+                                   Feature::TwoPieceConfigurations, Feature::DoubleMills, Feature::Mobility];
+
+    // the name of the feature in a configuration file
+    pub fn name(self) -> &'static str {
+        match self {
+            Feature::Material => "material",
+            Feature::ClosedMills => "closed_mills",
+            Feature::BlockedPieces => "blocked_pieces",
+            Feature::TwoPieceConfigurations => "two_piece_configurations",
+            Feature::DoubleMills => "double_mills",
+            Feature::Mobility => "mobility"
+        }
+    }
+}
+
+// the stage of the game, which has its own weights - the features matter differently once the pieces move, and again once a player flies
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Stage {
+    Placing,
+    Moving,
+    Flying  // the moving phase, when a player is down to 3 pieces and can fly
+}
+
+impl Stage {
+    pub const ALL: [Stage; 3] = [Stage::Placing, Stage::Moving, Stage::Flying];
+
+    // the name of the stage's section in a configuration file
+    pub fn name(self) -> &'static str {
+        match self {
+            Stage::Placing => "placing",
+            Stage::Moving => "moving",
+            Stage::Flying => "flying"
+        }
+    }
+
+    pub fn of(game: &Game) -> Self {
+        if game.phase() == Phase::Placing {
+            Stage::Placing
+        } else if game.rules().flying && [Color::White, Color::Black].iter().any(|color| game.pieces(*color).placed <= 3) {
+            Stage::Flying
+        } else {
+            Stage::Moving
+        }
+    }
+}
+
+// A static evaluation of positions - the weighted sum of the differences between the features (see Feature) of the current player
+// and of their opponent, with a separate weight of each feature for each stage of the game. A piece is worth 100 by default.
+// The weights can be tuned in a configuration file (see Evaluation.from_config() and evaluation/default.txt).
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Evaluation {
+    weights: [[i32; 6]; 3] // for each stage, the weight of each feature (in the order of Stage::ALL and Feature::ALL)
+}
+
+impl Default for Evaluation {
+    fn default() -> Self {
+        //                     material  closed  blocked  two-piece  double  mobility
+        Self { weights: [
+            /* placing */     [100,      20,     -2,      15,        10,     1],
+            /* moving */      [100,      15,     -10,     10,        30,     2],
+            /* flying */      [100,      10,     0,       20,        0,      0]
+        ] }
+    }
+}
+
+impl Evaluation {
+    pub fn weight(&self, stage: Stage, feature: Feature) -> i32 {
+        self.weights[stage as usize][feature as usize]
+    }
+
+    pub fn set_weight(&mut self, stage: Stage, feature: Feature, weight: i32) {
+        self.weights[stage as usize][feature as usize] = weight;
+    }
+
+    // Reads the weights from a configuration file - a section for each stage ("[placing]", "[moving]" or "[flying]"),
+    // with a line for each feature ("closed_mills = 15"). The weights which aren't given keep their default values.
+    // '#' starts a comment, which lasts to the end of the line.
+    pub fn from_config(config: &str) -> Result<Self, EvaluationError> {
+        let mut evaluation = Self::default();
+        let mut stage = None;
+
+        for (line, text) in config.lines().enumerate().map(|(i, text)| (i + 1, text)) {
+            let text = text.split('#').next().unwrap_or("").trim();
+            if text.is_empty() {
+                continue;
+            }
+
+            if let Some(name) = text.strip_prefix('[').and_then(|text| text.strip_suffix(']')) {
+                stage = Some(Stage::ALL.iter().copied().find(|stage| stage.name() == name.trim()).ok_or(EvaluationError::UnknownStage(line))?);
+                continue;
+            }
+
+            let (name, weight) = text.split_once('=').ok_or(EvaluationError::InvalidLine(line))?;
+            let stage = stage.ok_or(EvaluationError::InvalidLine(line))?;
+            let feature = Feature::ALL.iter().copied().find(|feature| feature.name() == name.trim()).ok_or(EvaluationError::UnknownFeature(line))?;
+            let weight = weight.trim().parse().map_err(|_| EvaluationError::InvalidWeight(line))?;
+            evaluation.set_weight(stage, feature, weight);
+        }
+
+        Ok(evaluation)
+    }
+
+    // Writes the weights in the format read by Evaluation.from_config().
+    pub fn to_config(&self) -> String {
+        let mut config = String::new();
+        for stage in Stage::ALL.iter() {
+            if !config.is_empty() {
+                config.push('\n');
+            }
+            config.push_str(&format!("[{}]\n", stage.name()));
+            for feature in Feature::ALL.iter() {
+                config.push_str(&format!("{} = {}\n", feature.name(), self.weight(*stage, *feature)));
+            }
+        }
+        config
+    }
+
+    // Returns the score of the game from the point of view of its current player. However large the weights are, the score stays
+    // strictly between -DECIDED and DECIDED, so that the search never mistakes it for a won or lost position.
+    pub fn evaluate(&self, game: &Game) -> i32 {
+        let weights = &self.weights[Stage::of(game) as usize];
+        let own = Self::features(game, game.current());
+        let opponent = Self::features(game, game.current().other());
+
+        let score: i64 = (0..Feature::ALL.len()).map(|i| weights[i] as i64 * (own[i] - opponent[i]) as i64).sum();
+        score.clamp(-DECIDED as i64 + 1, DECIDED as i64 - 1) as i32
+    }
+
+    // Counts the features of the given player's position (in the order of Feature::ALL).
+    pub fn features(game: &Game, color: Color) -> [i32; 6] {
+        let board = game.board();
+        let layout = board.layout();
+        let own = board.occupied(color);
+        let empty = board.empty();
+        let pieces = game.pieces(color);
+        let flying = game.phase() == Phase::Moving && game.rules().flying && pieces.placed <= 3;
+
+        let mills = layout.mills();
+        let closed = |mill: &&u32| **mill & own == **mill;
+        let closed_mills = mills.iter().filter(closed).count();
+        let two_piece = mills.iter().filter(|mill| (**mill & own).count_ones() == 2 && **mill & empty != 0).count();
+        let double_mills: usize = mills.iter().enumerate().filter(|(_, a)| closed(a))
+            .map(|(i, a)| mills[i + 1..].iter().filter(closed).filter(|b| a & **b != 0).count())
+            .sum();
+
+        let (mut blocked, mut mobility) = (0, 0);
+        for index in board.indices(own) {
+            let moves = if flying { empty.count_ones() } else { (layout.adjacent(index) & empty).count_ones() };
+            blocked += (moves == 0 && !flying) as i32;
+            mobility += moves as i32;
+        }
+
+        [
+            (pieces.placed + pieces.unplaced) as i32,
+            closed_mills as i32,
+            blocked,
+            two_piece as i32,
+            double_mills as i32,
+            mobility
+        ]
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Rules;
+
+    #[test]
+    fn test_features() {
+        // white: a mill a7 d7 g7 (which blocks a7 and d7), and a7 a4 of the a column with a1 empty; black: b6 d6 of a row with f6 empty
+        let mut game = Game::default();
+        for m in ["a7", "b6", "d7", "d6", "a4", "g1", "g7xg1"].iter() {
+            game.make_move(&game.parse_move(m).unwrap()).unwrap();
+        }
+
+        assert_eq!(Evaluation::features(&game, Color::White), [9, 1, 2, 1, 0, 3]);
+        assert_eq!(Evaluation::features(&game, Color::Black), [8, 0, 0, 1, 0, 3]);
+        assert_eq!(Stage::of(&game), Stage::Placing);
+
+        // black to move - a piece and a mill behind, but with fewer blocked pieces
+        assert_eq!(Evaluation::default().evaluate(&game), -100 - 20 + 2 * 2);
+
+        // huge weights don't make the score look like a won or lost game, or overflow
+        let huge = Evaluation::from_config("[placing]\nmaterial = 2000000").unwrap();
+        assert_eq!(huge.evaluate(&game), -DECIDED + 1);
+        let mut extreme = Evaluation::default();
+        for feature in Feature::ALL.iter() {
+            extreme.set_weight(Stage::Placing, *feature, i32::MIN);
+        }
+        assert_eq!(extreme.evaluate(&game), DECIDED - 1);
+    }
+
+    #[test]
+    fn test_blocked_and_double_mills() {
+        // white's piece at a4 is blocked, black's pieces form two mills sharing g7
+        let game = Game::from_notation("BBB/3/3/WW3B/3/3/W1B w m 0 0", Rules { flying: false, ..Rules::default() }).unwrap();
+
+        let white = Evaluation::features(&game, Color::White);
+        let black = Evaluation::features(&game, Color::Black);
+        assert_eq!(white[Feature::BlockedPieces as usize], 1);
+        assert_eq!(black[Feature::ClosedMills as usize], 2);
+        assert_eq!(black[Feature::DoubleMills as usize], 1);
+        assert_eq!(Stage::of(&game), Stage::Moving);
+    }
+
+    #[test]
+    fn test_config() {
+        let config = "# a comment\n[moving]\nmobility = 7  # another\n\n[ flying ]\nmaterial=150\n";
+        let evaluation = Evaluation::from_config(config).unwrap();
+        assert_eq!(evaluation.weight(Stage::Moving, Feature::Mobility), 7);
+        assert_eq!(evaluation.weight(Stage::Flying, Feature::Material), 150);
+        assert_eq!(evaluation.weight(Stage::Placing, Feature::Material), 100);
+
+        assert_eq!(Evaluation::from_config(&evaluation.to_config()), Ok(evaluation));
+        assert_eq!(Evaluation::from_config("mobility = 7"), Err(EvaluationError::InvalidLine(1)));
+        assert_eq!(Evaluation::from_config("[moving]\nmobility"), Err(EvaluationError::InvalidLine(2)));
+        assert_eq!(Evaluation::from_config("[endgame]"), Err(EvaluationError::UnknownStage(1)));
+        assert_eq!(Evaluation::from_config("[moving]\nspeed = 1"), Err(EvaluationError::UnknownFeature(2)));
+        assert_eq!(Evaluation::from_config("[moving]\nmobility = high"), Err(EvaluationError::InvalidWeight(2)));
+
+        // the example configuration has the default weights
+        let example = include_str!("../evaluation/default.txt");
+        assert_eq!(Evaluation::from_config(example), Ok(Evaluation::default()));
+    }
+}
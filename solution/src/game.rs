@@ -1,16 +1,23 @@
 use crate::pieces::Pieces;
 use crate::board::Board;
+use crate::action::Action;
+use crate::moves::{Move, MoveKind};
 use crate::enums::Phase;
+use crate::enums::State;
 use crate::enums::Color;
+use crate::enums::{GameResult, WinReason, DrawReason};
+use crate::point::Point;
 
-use crate::errors::PlacingError;
-use crate::errors::MovingError;
-use crate::errors::RemovingError;
-
-use std::io;
-use std::io::BufRead;
+use crate::errors::GameError;
+use crate::errors::ParseMoveError;
+use crate::errors::LoadError;
+use crate::errors::NotationError;
+use crate::player::{Player, GameView};
+use crate::rules::{Rules, DoubleMill, DrawRules};
+use crate::zobrist;
 
 
+#[derive(Clone)]
 pub struct Game {
     white: Pieces, // stores information about the white player's pieces
     black: Pieces, // stores information about the black player's pieces
@@ -18,19 +25,53 @@ pub struct Game {
     board: Board,
     phase: Phase,
 
-    current: Color // the player who should play on the current turn. Changes to the other color every turn
+    current: Color, // the player who should play on the current turn. Changes to the other color every turn
+    first: Color,   // the player who played (or will play) the first move
+    pending: Option<Move>, // a move which formed a mill - the current player should remove an opponent's piece before the turn ends
+    removals: u8,          // how many more pieces the current player should remove before the pending move is finished
+
+    history: Vec<Move>, // all moves played so far, in order
+    undone: Vec<Move>,  // moves which were undone and can be redone, the most recently undone one last
+    positions: Vec<u64>, // the hash of the position before the first move and after each move in the history, for detecting repetitions
+
+    rules: Rules,
+
+    ended: Option<GameResult> // set if the game was ended by something other than the state of the board (resignation or timeout)
 }
+
 impl Game {
-    pub fn new() -> Self {
-        Self {
-            white: Pieces::new(), 
-            black: Pieces::new(), 
+    // A new game played by the given rules - Game::default() plays by the standard rules.
+    // Panics if the board can't hold all the pieces (see Rules.fits()).
+    pub fn new(rules: Rules) -> Self {
+        assert!(rules.fits(), "ERROR: the board can't hold all the pieces of both players");
+        let mut game = Self {
+            white: Pieces::new(rules.pieces),
+            black: Pieces::new(rules.pieces),
 
-            board: Board::new(),
+            board: Board::with_layout(rules.layout.clone()),
             phase: Phase::Placing,
 
-            current: Color::White 
-        }
+            current: Color::White,
+            first: Color::White,
+            pending: None,
+            removals: 0,
+
+            history: Vec::new(),
+            undone: Vec::new(),
+            positions: Vec::new(),
+
+            rules,
+
+            ended: None
+        };
+        game.positions.push(game.hash());
+        game
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new(Rules::default())
     }
 }
 
@@ -45,363 +86,1698 @@ impl Display for Game {
     }
 }
 
+impl Game { // accessors
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+    pub fn current(&self) -> Color {
+        self.current
+    }
+    pub fn pieces(&self, color: Color) -> &Pieces {
+        match color {
+            Color::White => &self.white,
+            Color::Black => &self.black
+        }
+    }
+
+    // Sets the player who should play on the current turn - used to choose who moves first.
+    pub fn set_current(&mut self, color: Color) {
+        self.current = color;
+        if self.history.is_empty() {
+            self.first = color;
+            self.positions = vec![self.hash()];
+        }
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+    pub fn draw_rules(&self) -> DrawRules {
+        self.rules.draw
+    }
+    // Sets the rules which end the game in a draw - by default, threefold repetition and 50 moves without a mill.
+    pub fn set_draw_rules(&mut self, rules: DrawRules) {
+        self.rules.draw = rules;
+    }
+
+    // Returns the player who played (or will play) the first move.
+    pub fn first(&self) -> Color {
+        self.first
+    }
+
+    // Returns true if the current player has formed a mill and the next action has to be Action::Remove.
+    pub fn removal_pending(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    // Returns all moves played so far (not including undone ones), in order.
+    pub fn history(&self) -> &[Move] {
+        &self.history
+    }
+
+    // Returns true if the current player can "fly" their pieces, i.e. move them to non-adjacent positions.
+    pub fn flying(&self) -> bool {
+        self.rules.flying && self.counted_pieces(self.current) <= 3
+    }
+
+    // The number of pieces of the given player which count towards flying and losing - the ones on the board,
+    // and also the unplaced ones when placing and moving are interleaved (otherwise, these only matter after all pieces are placed).
+    fn counted_pieces(&self, color: Color) -> u8 {
+        let pieces = self.pieces(color);
+        if self.rules.interleaved { pieces.placed + pieces.unplaced } else { pieces.placed }
+    }
+
+    // Returns true if pieces can be moved in the current phase - always in the moving phase, and also in the placing phase if the rules interleave them.
+    fn moving_allowed(&self) -> bool {
+        self.phase == Phase::Moving || self.rules.interleaved
+    }
+
+    fn pieces_mut(&mut self, color: Color) -> &mut Pieces {
+        match color {
+            Color::White => &mut self.white,
+            Color::Black => &mut self.black
+        }
+    }
+}
+
 impl Game { // game actions
-    // Requests the coordinates of a single position, reads them from the standard input and calls Board.place() with the color of the current player and said position.
-    // Handles that function's errors and prints an approriate message if there is one.
-    // If the placement is successful, calls the Pieces.place() function of the current player and returns the position.
-    fn place_piece<R: BufRead>(&mut self, mut input: R) -> String {
-        println!("{} player, PLACE your piece:", self.current);
+    // Performs a single action for the current player, without any input or output.
+    // Placing and moving are only valid in their respective phases and when no removal is pending;
+    // removing is only valid right after a mill has been formed.
+    // If the action forms a mill, the turn doesn't end until a removal is applied - otherwise the turn passes to the other player.
+    pub fn apply(&mut self, action: Action) -> Result<(), GameError> {
+        if self.ended.is_some() || (self.pending.is_none() && self.is_over()) {
+            return Err(GameError::GameOver);
+        }
 
-        loop {
-            let mut position = String::new();
-            match input.read_line(&mut position) {
-                Err(error) => println!("ERROR: input error - {}", error),
-                Ok(_) => {
-                    let trimmed_position = position.trim_end();
-                    match self.board.place_piece(self.current, trimmed_position) {
-                        Err(PlacingError::InvalidPosition) => println!("ERROR: Invalid position - {}!", trimmed_position),
-                        Err(PlacingError::PlaceAtOccupied) => println!("ERROR: Position {} is already occupied!", trimmed_position),
-                        Ok(()) => {
-                            match self.current {
-                                Color::White => self.white.place_piece(trimmed_position.to_string()),
-                                Color::Black => self.black.place_piece(trimmed_position.to_string())
-                            }
+        match action {
+            Action::Place(position) => {
+                if self.pending.is_some() {
+                    return Err(GameError::RemovalExpected);
+                }
+                if self.phase != Phase::Placing {
+                    return Err(GameError::WrongPhase);
+                }
+                if self.pieces(self.current).unplaced == 0 {
+                    return Err(GameError::NothingToPlace);
+                }
 
-                            return trimmed_position.to_string();
-                        }
-                    }
+                self.place_piece(&position)?;
+                self.after_placing_or_moving(MoveKind::Place(Self::point(&position)));
+            }
+            Action::Move(from, to) => {
+                if self.pending.is_some() {
+                    return Err(GameError::RemovalExpected);
+                }
+                if !self.moving_allowed() {
+                    return Err(GameError::WrongPhase);
+                }
+
+                let flying = self.flying();
+                self.move_piece(&from, &to, flying)?;
+                if flying {
+                    self.after_placing_or_moving(MoveKind::Fly(Self::point(&from), Self::point(&to)));
+                } else {
+                    self.after_placing_or_moving(MoveKind::Step(Self::point(&from), Self::point(&to)));
                 }
             }
+            Action::Remove(position) => {
+                let m = match self.pending {
+                    None => return Err(GameError::RemovalUnexpected),
+                    Some(m) => m
+                };
+
+                self.remove_piece(&position)?;
+                let m = m.with_capture(Self::point(&position));
+
+                // a second removal (after a double mill) is skipped if the opponent has no pieces left
+                self.removals -= 1;
+                if self.removals == 0 || self.pieces(self.current.other()).placed == 0 {
+                    self.pending = None;
+                    self.removals = 0;
+                    self.end_turn(m);
+                } else {
+                    self.pending = Some(m);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Calls Board.place() with the color of the current player and the given position.
+    // If the placement is successful, calls the Pieces.place() function of the current player.
+    fn place_piece(&mut self, position: &str) -> Result<(), GameError> {
+        self.board.place_piece(self.current, position)?;
+        self.pieces_mut(self.current).place_piece(position.to_string());
+
+        Ok(())
+    }
+
+    // Calls Board.move() with the color of the current player, both positions, and a boolean value determining whether the piece can "fly".
+    // If the movement is successful, calls the Pieces.move() function of the current player.
+    fn move_piece(&mut self, from: &str, to: &str, flying: bool) -> Result<(), GameError> {
+        self.board.move_piece(self.current, from, to, flying)?;
+        self.pieces_mut(self.current).move_piece(from.to_string(), to.to_string());
+
+        Ok(())
+    }
+
+    // Calls Board.remove() with the color of the other player, the position and an appropriate boolean value determining whether pieces can be removed from mills or not.
+    // If the removal is successful, calls the Pieces.remove() function of the other player.
+    fn remove_piece(&mut self, position: &str) -> Result<(), GameError> {
+        let other = self.current.other();
+
+        let check_for_mills = self.mills_protected(&self.board, other);
+
+        self.board.remove_piece(other, position, check_for_mills)?;
+        self.pieces_mut(other).remove_piece(position.to_string());
+
+        Ok(())
+    }
+
+    // If all of the given player's pieces are in a mill, they can be removed without issue, so mills aren't protected (and the result is false). 
+    // Otherwise, only pieces not in a mill can be removed, so the result is true (unless the rules never protect mills).
+    fn mills_protected(&self, board: &Board, color: Color) -> bool {
+        self.rules.protect_mills && board.in_mills(color) != board.occupied(color)
+    }
+
+    // If the piece that was just placed or moved formed a mill, the current player has to remove a piece before their turn ends
+    // (or two pieces, if it formed two mills and the rules allow it). There is nothing to remove if the opponent has no pieces on the board.
+    fn after_placing_or_moving(&mut self, kind: MoveKind) {
+        let m = Move { kind, capture: None, second_capture: None };
+        let index = self.board.layout().index(&m.to().to_string()).expect("ERROR: invalid position on the board");
+        let opponent = self.board.occupied(self.current.other()).count_ones() as usize;
+
+        let removals = self.removals_for(self.mills_formed(self.board.occupied(self.current), index), opponent);
+        if removals > 0 {
+            self.pending = Some(m);
+            self.removals = removals as u8;
+        } else {
+            self.end_turn(m);
         }
     }
-    
-    // Requests the coordinates of two positions, reads a single line from the standard input and checks if it is of the appropriate length.
-    // If yes, also checks whether the current player can "fly" their pieces and calls Board.move() with the color of the current player, both positions,
-    // and an appropriate boolean value for the "flying".
-    // Handles that function's errors and prints an approriate message if there is one.
-    // If the movement is successful, calls the Pieces.move() function of the current player and returns the second position.
-    fn move_piece<R: BufRead>(&mut self, mut input: R) -> String {
-        let flying = match self.current {
-            Color::White => self.white.placed <= 3,
-            Color::Black => self.black.placed <= 3
+
+    // The number of mills the current player forms with a piece arriving at the given index, given their pieces after the move.
+    // Unless the rules allow re-forming mills, a mill the player broke on their previous turn by moving a piece out of it doesn't count
+    // when it's completed again right away - it's a mill at the position that piece left, which doesn't include where the piece went.
+    fn mills_formed(&self, own: u32, to: usize) -> usize {
+        let layout = self.board.layout();
+        let index = |point: Point| layout.index(&point.to_string()).expect("ERROR: invalid position on the board");
+
+        // where the player's previous move went, if it left the position this move goes to
+        let returning = match self.history.len().checked_sub(2).map(|i| self.history[i].kind) {
+            Some(MoveKind::Step(from, previous)) | Some(MoveKind::Fly(from, previous)) if !self.rules.reform_mills && index(from) == to => Some(index(previous)),
+            _ => None
         };
-        if flying {
-            println!("{} player, MOVE your piece to any position ('fly'):", self.current);
+
+        layout.mills_at(to).iter()
+            .filter(|&&mill| own & mill == mill && returning.map_or(true, |previous| mill & 1 << previous != 0))
+            .count()
+    }
+
+    // the number of pieces to remove after forming the given number of mills with one move, when the opponent has the given number of pieces
+    fn removals_for(&self, mills: usize, opponent: usize) -> usize {
+        let allowed = match self.rules.double_mill {
+            DoubleMill::OneCapture => 1,
+            DoubleMill::TwoCaptures => 2
+        };
+        mills.min(allowed).min(opponent)
+    }
+
+    // Records the finished move in the history (which makes the undone moves unreachable) and passes the turn to the other player.
+    fn end_turn(&mut self, m: Move) {
+        self.history.push(m);
+        self.undone.clear();
+
+        self.current = self.current.other();
+        self.update_phase();
+        self.positions.push(self.hash());
+    }
+
+    fn update_phase(&mut self) {
+        if self.white.unplaced == 0 && self.black.unplaced == 0 {
+            self.phase = Phase::Moving;
         } else {
-            println!("{} player, MOVE your piece to an adjacent position:", self.current);
+            self.phase = Phase::Placing;
         }
+    }
+}
 
-        loop {
-            let mut positions = String::new();
-            match input.read_line(&mut positions) {
-                Err(error) => println!("ERROR: input error - {}", error),
-                Ok(6) => { // letter + digit + letter + digit + \r + \n
-                    let trimmed_positions = positions.trim_end();
-                    let start = &trimmed_positions[..2];
-                    let end = &trimmed_positions[2..];
-
-
-                    match self.board.move_piece(self.current, start, end, flying) {
-                        Err(MovingError::InvalidMoveFrom)    => println!("ERROR: Invalid first position - {}!", start),
-                        Err(MovingError::InvalidMoveTo)      => println!("ERROR: Invalid second position - {}!", end),
-                        Err(MovingError::MoveToSame)         => println!("ERROR: The two positions are identical!"),
-                        Err(MovingError::MoveFromEmpty)      => println!("ERROR: The starting position {} doesn't have a piece to move!", start),
-                        Err(MovingError::MoveFromWrongColor) => println!("ERROR: The starting position {} isn't occupied by you!", start),
-                        Err(MovingError::MoveToOccupied)     => println!("ERROR: The target position {} is already occupied!", end),
-                        Err(MovingError::NotAdjacent)        => println!("ERROR: Can't move from {} to {}!", start, end),
-                        Ok(()) => {
-                            match self.current {
-                                Color::White => self.white.move_piece(start.to_string(), end.to_string()),
-                                Color::Black => self.black.move_piece(start.to_string(), end.to_string())
-                            }
+impl Game { // whole moves
+    // Performs a whole turn of the current player - placing or moving a piece, and removing the captured piece if a mill is formed.
+    // The move is applied atomically: if any part of it is invalid (including a missing or unexpected capture), an error is returned and the game is left unchanged.
+    pub fn make_move(&mut self, m: &Move) -> Result<(), GameError> {
+        if self.pending.is_some() {
+            return Err(GameError::RemovalExpected);
+        }
+        if self.is_over() {
+            return Err(GameError::GameOver);
+        }
 
-                            return end.to_string();
-                        }
-                    }
+        // the undone moves are only discarded if the move succeeds
+        let undone = std::mem::take(&mut self.undone);
+        let result = self.perform(m);
+        if result.is_err() {
+            self.undone = undone;
+        }
+
+        result
+    }
+
+    // Applies the parts of a move one after another - if one of them fails, the ones already applied are taken back.
+    fn perform(&mut self, m: &Move) -> Result<(), GameError> {
+        match m.kind {
+            MoveKind::Place(to) => {
+                if self.phase != Phase::Placing {
+                    return Err(GameError::WrongPhase);
+                }
+                if self.pieces(self.current).unplaced == 0 {
+                    return Err(GameError::NothingToPlace);
+                }
+
+                self.place_piece(&to.to_string())?;
+            }
+            MoveKind::Step(from, to) => {
+                if !self.moving_allowed() {
+                    return Err(GameError::WrongPhase);
+                }
+
+                self.move_piece(&from.to_string(), &to.to_string(), false)?;
+            }
+            MoveKind::Fly(from, to) => {
+                if !self.moving_allowed() {
+                    return Err(GameError::WrongPhase);
                 }
-                Ok(_) => println!("ERROR: Invalid input - must be 4 symbols (e.g. a7a4)!"),
+                if !self.flying() {
+                    return Err(GameError::FlyingNotAllowed);
+                }
+
+                self.move_piece(&from.to_string(), &to.to_string(), true)?;
+            }
+        }
+        self.after_placing_or_moving(m.kind);
+
+        let mut result = Ok(());
+        for capture in m.captures() {
+            result = match self.pending {
+                Some(_) => self.apply(Action::Remove(capture.to_string())),
+                None => Err(GameError::RemovalUnexpected)
+            };
+            if result.is_err() {
+                break;
             }
         }
+        if result.is_ok() && self.pending.is_some() {
+            result = Err(GameError::RemovalExpected);
+        }
+        if result.is_err() {
+            self.undo().expect("ERROR: could not take back a partially applied move");
+        }
+
+        result
     }
 
-    // Requests the coordinates of a single position, reads them from the standard input and calls Board.remove() 
-    // with the color of the current player, the position and an appropriate boolean value determining whether pieces can be removed from mills or not.
-    // Handles that function's errors and prints an approriate message if there is one.
-    // If the removal is successful, calls the Pieces.remove() function of the other player.
-    fn remove_piece<R: BufRead>(&mut self, mut input: R) -> String {
-        println!("{} player, REMOVE opponent's piece:", self.current);
+    // Parses a move for the current player - like Move::from_str(), except that steps (e.g. "a7a4" or "a7-a4") are treated as flying
+    // if the current player can fly, as all of their moves are (see legal_moves()).
+    pub fn parse_move(&self, s: &str) -> Result<Move, ParseMoveError> {
+        let mut parsed: Move = s.parse()?;
+        if let MoveKind::Step(from, to) = parsed.kind {
+            if self.moving_allowed() && self.flying() {
+                parsed.kind = MoveKind::Fly(from, to);
+            }
+        }
 
-        loop {
-            let mut position = String::new();
-            match input.read_line(&mut position) {
-                Err(error) => println!("ERROR: input error - {}", error),
-                Ok(_) => {
-                    // If all of the other player's pieces are in a mill, they can be removed without issue, so check_for_mills is false. 
-                    // Otherwise, only pieces not in a mill can be removed, so check_for_mills is true.
-                    let check_for_mills = match self.current {
-                        Color::White => !self.black.positions.iter().all(|position| self.board.in_mill(Color::Black, position)),
-                        Color::Black => !self.white.positions.iter().all(|position| self.board.in_mill(Color::White, position)),
-                    };
-
-                    let trimmed_position = position.trim_end();
-                    match self.board.remove_piece(self.current.other(), trimmed_position, check_for_mills) {
-                        Err(RemovingError::InvalidPosition)      => println!("ERROR: Invalid position - {}!", trimmed_position),
-                        Err(RemovingError::RemoveFromEmpty)      => println!("ERROR: Cannot remove from empty position {}!", trimmed_position),
-                        Err(RemovingError::RemoveFromWrongColor) => println!("ERROR: Cannot remove your own pieces (from position {})!", trimmed_position),
-                        Err(RemovingError::RemoveFromMill)       => println!("ERROR: Cannot remove from opponent's mills (from position {})!", trimmed_position),
-                        Ok(()) => {
-                            match self.current {
-                                Color::White => self.black.remove_piece(trimmed_position.to_string()),
-                                Color::Black => self.white.remove_piece(trimmed_position.to_string())
-                            }
+        Ok(parsed)
+    }
+}
+
+impl Game { // history
+    // Takes back the last move, restoring the board, the pieces of both players, the phase and the current player.
+    // If the current player has formed a mill but hasn't removed a piece yet, only that half of the turn is taken back (and it can't be redone).
+    pub fn undo(&mut self) -> Result<(), GameError> {
+        if let Some(m) = self.pending.take() {
+            self.revert(&m, self.current);
+            self.removals = 0;
+            return Ok(());
+        }
+
+        match self.history.pop() {
+            None => Err(GameError::NothingToUndo),
+            Some(m) => {
+                let color = self.current.other();
+                self.revert(&m, color);
+                self.current = color;
+                self.undone.push(m);
+                self.positions.pop();
+
+                Ok(())
+            }
+        }
+    }
+
+    // Plays the last undone move again.
+    pub fn redo(&mut self) -> Result<(), GameError> {
+        let m = match self.undone.pop() {
+            None => return Err(GameError::NothingToRedo),
+            Some(m) => m
+        };
+
+        let undone = std::mem::take(&mut self.undone);
+        let result = self.make_move(&m);
+        self.undone = undone;
+        if result.is_err() {
+            self.undone.push(m);
+        }
+
+        result
+    }
+
+    // Reverts the effects of a move made by the player of the given color - the move is assumed to have been the last one made.
+    fn revert(&mut self, m: &Move, color: Color) {
+        let other = color.other();
+        for capture in m.captures() {
+            let capture = capture.to_string();
+            self.board.place_piece(other, &capture).expect("ERROR: invalid history");
+            self.pieces_mut(other).restore_piece(capture);
+        }
+
+        match m.kind {
+            MoveKind::Place(to) => {
+                let to = to.to_string();
+                self.board.remove_piece(color, &to, false).expect("ERROR: invalid history");
+                self.pieces_mut(color).unplace_piece(to);
+            }
+            MoveKind::Step(from, to) | MoveKind::Fly(from, to) => {
+                let (from, to) = (from.to_string(), to.to_string());
+                self.board.move_piece(color, &to, &from, true).expect("ERROR: invalid history");
+                self.pieces_mut(color).move_piece(to, from);
+            }
+        }
+
+        self.update_phase();
+    }
+}
 
-                            return trimmed_position.to_string();
+impl Game { // move generation
+    // Returns every legal move of the current player, including every possible capture when a move forms a mill.
+    // The moves are generated in a deterministic order. If the game is over (or a removal is pending), there are no legal moves.
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let mut moves = Vec::new();
+        if self.pending.is_some() || self.is_over() {
+            return moves;
+        }
+
+        let board = &self.board;
+        let color = self.current;
+        let own = board.occupied(color);
+        let empty = board.empty();
+
+        // pairs of (position the piece is taken from, position it ends up at) - the first is None when placing
+        let mut targets = Vec::new();
+        if self.phase == Phase::Placing && self.pieces(color).unplaced > 0 {
+            targets.extend(board.indices(empty).map(|to| (None, to)));
+        }
+        if self.moving_allowed() {
+            let flying = self.flying();
+            for from in board.indices(own) {
+                let destinations = if flying { empty } else { board.layout().adjacent(from) & empty };
+                targets.extend(board.indices(destinations).map(|to| (Some(from), to)));
+            }
+        }
+
+        // Moving pieces of one color can't change which of the other color's pieces are in mills, so the capturable pieces are the same for every move.
+        let opponent = board.occupied(color.other());
+        let capturable = self.capturable(opponent);
+
+        for (from, to) in targets {
+            let (kind, after) = match from {
+                None => (MoveKind::Place(board.point(to)), own | 1 << to),
+                Some(from) if self.flying() => (MoveKind::Fly(board.point(from), board.point(to)), own ^ (1 << from | 1 << to)),
+                Some(from) => (MoveKind::Step(board.point(from), board.point(to)), own ^ (1 << from | 1 << to))
+            };
+            let m = Move { kind, capture: None, second_capture: None };
+
+            match self.removals_for(self.mills_formed(after, to), opponent.count_ones() as usize) {
+                0 => moves.push(m),
+                1 => moves.extend(board.indices(capturable).map(|capture| m.with_capture(board.point(capture)))),
+                _ => {
+                    // removing the first piece can change which of the remaining ones are protected, so the second capture depends on the first.
+                    // Both orders of the same two captures are only listed once, unless only one of them is legal.
+                    for first in board.indices(capturable) {
+                        let remaining = opponent & !(1 << first);
+                        for second in board.indices(self.capturable(remaining)) {
+                            if first < second || self.capturable(opponent & !(1 << second)) & 1 << first == 0 {
+                                moves.push(m.with_capture(board.point(first)).with_capture(board.point(second)));
+                            }
                         }
                     }
                 }
             }
         }
-    
+
+        moves
+    }
+
+    // a mask of the opponent's pieces (given as a mask) which can be removed - the ones not in mills, or all of them if they are all in mills
+    fn capturable(&self, opponent: u32) -> u32 {
+        let in_mills = self.board.in_mills_with(opponent);
+        if !self.rules.protect_mills || in_mills == opponent { opponent } else { opponent & !in_mills }
+    }
+
+    // converts a position on the board to a Point - the board only contains valid positions, so this can't fail
+    fn point(position: &str) -> Point {
+        position.parse().expect("ERROR: invalid position on the board")
     }
 }
 
 impl Game { // checking if the game can continue
     // checks if the player of the given color can move at least one of their pieces
-    fn can_move(&self, color: Color) -> bool {
-        let flying = match self.current {
-            Color::White => self.white.placed <= 3,
-            Color::Black => self.black.placed <= 3
-        };
-        if flying { // there are at most 18 pieces on a board of 24 positions, so there will always be an available position to fly to
-            return true;
+    pub fn can_move(&self, color: Color) -> bool {
+        let flying = self.rules.flying && self.counted_pieces(color) <= 3;
+        let empty = self.board.empty();
+        if flying {
+            return empty != 0;
         }
 
-        match color {
-            Color::White => self.white.positions.iter().any(|position| self.board.can_move(Color::White, position)),
-            Color::Black => self.black.positions.iter().any(|position| self.board.can_move(Color::Black, position))
-        }
+        self.board.indices(self.board.occupied(color)).any(|index| self.board.layout().adjacent(index) & empty != 0)
     }
 
     // checks if the player of the given color can continue playing the game, i.e. if they have enough pieces and can move at least one
-    fn can_play(&self, color: Color) -> bool {
-        match color {
-            Color::White => self.white.placed >= 3 && self.can_move(Color::White),
-            Color::Black => self.black.placed >= 3 && self.can_move(Color::Black)
+    // (or place one, when placing and moving are interleaved)
+    pub fn can_play(&self, color: Color) -> bool {
+        !self.too_few_pieces(color) && self.has_move(color)
+    }
+
+    fn too_few_pieces(&self, color: Color) -> bool {
+        self.counted_pieces(color) < 3
+    }
+
+    // Checks if the player of the given color can move a piece, or place one if the rules interleave placing and moving.
+    fn has_move(&self, color: Color) -> bool {
+        let can_place = self.rules.interleaved && self.pieces(color).unplaced > 0 && self.board.empty() != 0;
+        can_place || self.can_move(color)
+    }
+
+    // checks if the game has ended - it continues while in the placing phase, or while both players can play in the moving phase
+    pub fn is_over(&self) -> bool {
+        self.outcome().is_some()
+    }
+
+    // Returns the result of the game, or None if it hasn't ended yet.
+    // A player loses if they have less than 3 pieces or cannot move any of them - if neither player can move, the game is a draw.
+    // The game is also drawn by the draw rules (repeating a position or making too many moves without a mill).
+    pub fn outcome(&self) -> Option<GameResult> {
+        if self.ended.is_some() {
+            return self.ended;
+        }
+        if self.phase == Phase::Placing && !self.rules.interleaved {
+            return None;
+        }
+        if self.can_play(Color::White) && self.can_play(Color::Black) {
+            return self.draw();
+        }
+
+        if self.too_few_pieces(Color::White) {
+            Some(GameResult::Win { winner: Color::Black, reason: WinReason::TooFewPieces })
+        } else if self.too_few_pieces(Color::Black) {
+            Some(GameResult::Win { winner: Color::White, reason: WinReason::TooFewPieces })
+        } else if self.has_move(Color::White) {
+            Some(GameResult::Win { winner: Color::White, reason: WinReason::Blocked })
+        } else if self.has_move(Color::Black) {
+            Some(GameResult::Win { winner: Color::Black, reason: WinReason::Blocked })
+        } else {
+            Some(GameResult::Draw { reason: DrawReason::BothBlocked })
         }
     }
-}
 
-impl Game { // game loop
-    // Requests information about which player should move first.
-    // After that, loops while the game can continue (both players have enough pieces and can move at least one of them), with each iteration
-    // calling either place() or move() depending on the phase of the game. If a mill is formed, calls remove().
-    // After the looping condition becomes false, prints an appropriate message for the end of the game, 
-    // describing who won and by what cause in the case of victory, or that the outcome is a draw.
-    pub fn game_loop(&mut self) {
+    // Checks the draw rules - returns the draw if one of them applies.
+    fn draw(&self) -> Option<GameResult> {
+        if let Some(limit) = self.rules.draw.repetitions {
+            if self.repetitions() >= limit {
+                return Some(GameResult::Draw { reason: DrawReason::Repetition(limit) });
+            }
+        }
+        if let Some(limit) = self.rules.draw.moves_without_mill {
+            if self.moves_without_mill() >= limit {
+                return Some(GameResult::Draw { reason: DrawReason::NoMills(limit) });
+            }
+        }
+
+        None
+    }
+
+    // Returns how many times the current position has occurred (including now).
+    // Placing or capturing a piece can't be undone, so only the positions since the last such move need to be checked.
+    pub fn repetitions(&self) -> u32 {
+        let current = self.positions.last().expect("ERROR: no positions recorded");
+        let mut count = 1;
+        for (i, m) in self.history.iter().enumerate().rev() {
+            if m.capture.is_some() || matches!(m.kind, MoveKind::Place(_)) {
+                break;
+            }
+            if self.positions[i] == *current {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    // Returns how many moves in a row (by either player) were made in the moving phase without forming a mill.
+    pub fn moves_without_mill(&self) -> u32 {
+        self.history.iter().rev()
+            .take_while(|m| m.capture.is_none() && !matches!(m.kind, MoveKind::Place(_)))
+            .count() as u32
+    }
+
+    // Returns the Zobrist hash of the position - the pieces on the board, the side to move, the phase and the unplaced pieces of both players.
+    // The hash of the board is updated with every change to it, so this only adds the few other parts. It's the same in every run.
+    pub fn hash(&self) -> u64 {
+        self.board.hash()
+            ^ zobrist::side(self.current)
+            ^ zobrist::phase(self.phase)
+            ^ zobrist::unplaced(Color::White, self.white.unplaced)
+            ^ zobrist::unplaced(Color::Black, self.black.unplaced)
+    }
+
+    // Returns the Zobrist hash of the canonical version of the position (see Board.canonical()), which is the same for all positions
+    // symmetric to each other - e.g. for detecting that two games reached equivalent positions.
+    pub fn canonical_hash(&self) -> u64 {
+        self.hash() ^ self.board.hash() ^ self.board.canonical().0.hash()
+    }
+
+    // Plays the game until it ends, asking the given players for their moves in turn, and returns the result.
+    // A player who resigns (by not choosing a move) or chooses an illegal move loses.
+    pub fn play(&mut self, white: &mut dyn Player, black: &mut dyn Player) -> GameResult {
         loop {
-            println!("Who should move first? (white or black)?");
-
-            let mut input = String::new();
-            match io::stdin().read_line(&mut input) {
-                Err(error) => println!("ERROR: input error - {}", error),
-                Ok(_) => {
-                    let trimmed = input.trim_end();
-
-                    if trimmed == "white" {
-                        self.current = Color::White;
-                        break;
-                    } else if trimmed == "black" {
-                        self.current = Color::Black;
-                        break;
-                    } else {
-                        println!("ERROR: Invalid input!");
+            if let Some(result) = self.outcome() {
+                return result;
+            }
+
+            let color = self.current;
+            let choice = match color {
+                Color::White => white.choose_move(&GameView::new(self)),
+                Color::Black => black.choose_move(&GameView::new(self))
+            };
+            match choice {
+                None => self.resign(color).expect("ERROR: the game is already over"),
+                Some(m) => {
+                    if self.make_move(&m).is_err() {
+                        self.end(GameResult::Win { winner: color.other(), reason: WinReason::IllegalMove }).expect("ERROR: the game is already over");
                     }
                 }
             }
-        }    
-
-        print!("\nINSTRUCTIONS:\n\
-                At the start of every turn, the board is printed, with the occupied positions marked by ○ for white pieces and ● for black pieces.\n\
-                
-                Depending on the phase of the game, the players are asked to input coordinates of positions:\n\
-                - during the placement phase, input the coordinates of a single position (e.g. 'a7') to place a piece there. The position should be unoccupied.\n\
-                - during the movement phase, input the coordinates of two positions (e.g. 'a7a4') to move a piece from the first to the second. \
-                The first position should have a piece of your color, and the second should be adjacent to it and unoccupied.\n\
-                If you have only 3 pieces left however, you can move to non-adjacent positions.\n\
-                \n\
-                If a mill is formed at any point, a message will be printed and you will need to input the coordinates of a single position, \
-                from which to remove a piece belonging to your opponent.\n\
-
-                In the case of invalid input (positions that don't exist, placing on already occupied positions, moving your opponent's pieces, etc.) \
-                an appropriate error message is printed and the turn is repeated (until correct input is given).
-                \n"
-        );
-
-        while self.phase == Phase::Placing || (self.phase == Phase::Moving && self.can_play(Color::White) && self.can_play(Color::Black)) {
-            print!("{}", self);
-
-            let position = match self.phase {
-                Phase::Placing => self.place_piece(io::stdin().lock()),
-                Phase::Moving  => self.move_piece(io::stdin().lock())
-            };
+        }
+    }
+
+    // Ends the game with a victory for the opponent of the player of the given color, who resigned.
+    pub fn resign(&mut self, color: Color) -> Result<(), GameError> {
+        self.end(GameResult::Win { winner: color.other(), reason: WinReason::Resignation })
+    }
+
+    // Ends the game with a victory for the opponent of the player of the given color, who ran out of time.
+    pub fn timeout(&mut self, color: Color) -> Result<(), GameError> {
+        self.end(GameResult::Win { winner: color.other(), reason: WinReason::Timeout })
+    }
+
+    fn end(&mut self, result: GameResult) -> Result<(), GameError> {
+        if self.is_over() {
+            return Err(GameError::GameOver);
+        }
+
+        self.pending = None;
+        self.removals = 0;
+        self.ended = Some(result);
+        Ok(())
+    }
+}
 
-            if self.board.in_mill(self.current, &position) {
-                print!("{}", self);
-                println!("{} player FORMED A MILL!", self.current);
+impl Game { // saving and loading
+    // Returns the game as text - the player who moved first, followed by the moves played so far (one per line),
+    // and how the game ended if it wasn't decided by the board (resignation, timeout or an illegal move), e.g.:
+    //   first: white
+    //   a7
+    //   a1
+    //   resigned: black
+    // Undone moves and a mill without a removal yet are not saved.
+    pub fn save(&self) -> String {
+        let mut text = format!("first: {}\n", self.first.name());
+        for m in self.history.iter() {
+            text += &format!("{}\n", m);
+        }
 
-                self.remove_piece(io::stdin().lock());
+        if let Some(GameResult::Win { winner, reason }) = self.ended {
+            let loser = winner.other().name();
+            match reason {
+                WinReason::Resignation => text += &format!("resigned: {}\n", loser),
+                WinReason::Timeout     => text += &format!("timeout: {}\n", loser),
+                WinReason::IllegalMove => text += &format!("illegal: {}\n", loser),
+                _ => {}
             }
+        }
+
+        text
+    }
 
-            self.current = self.current.other();
+    // Reconstructs a game saved by save(), by replaying its moves from the starting position with the given rules (which aren't saved).
+    // Empty lines and lines starting with '#' are ignored. Errors report the (1-based) number of the offending line.
+    pub fn load(text: &str, rules: Rules) -> Result<Game, LoadError> {
+        let mut lines = text.lines().enumerate()
+            .map(|(i, line)| (i + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
 
-            if self.white.unplaced == 0 && self.black.unplaced == 0 {
-                self.phase = Phase::Moving;
+        let mut game = Game::new(rules);
+        match lines.next() {
+            Some((_, line)) if line.starts_with("first:") => {
+                let first = Color::from_name(line["first:".len()..].trim()).ok_or(LoadError::MissingFirst)?;
+                game.set_current(first);
             }
+            _ => return Err(LoadError::MissingFirst)
         }
 
-        print!("{}", self);
-        if self.white.placed == 2 {
-            println!("VICTORY for BLACK player - white player has less than 3 pieces!");
-        } else if self.black.placed == 2 {
-            println!("VICTORY for WHITE player - black player has less than 3 pieces!");
-        } else if self.can_move(Color::White) == false && self.can_move(Color::Black) {
-            println!("VICTORY for BLACK player - white player cannot move their pieces!")
-        } else if self.can_move(Color::White) && self.can_move(Color::Black) == false {
-            println!("VICTORY for WHITE player - black player cannot move their pieces!")
-        } else if self.can_move(Color::White) == false && self.can_move(Color::Black) == false {
-            println!("DRAW - neither player can move their pieces!")
-        } else {
-            println!("ERROR: invalid end state of game!")
+        for (number, line) in lines {
+            if game.ended.is_some() {
+                return Err(LoadError::InvalidResult(number));
+            }
+
+            if let Some((key, value)) = line.split_once(':') {
+                let color = Color::from_name(value.trim()).ok_or(LoadError::InvalidResult(number))?;
+                let reason = match key.trim() {
+                    "resigned" => WinReason::Resignation,
+                    "timeout"  => WinReason::Timeout,
+                    "illegal"  => WinReason::IllegalMove,
+                    _ => return Err(LoadError::InvalidResult(number))
+                };
+                game.end(GameResult::Win { winner: color.other(), reason }).map_err(|_| LoadError::InvalidResult(number))?;
+                continue;
+            }
+
+            let m = game.parse_move(line).map_err(|_| LoadError::InvalidMove(number))?;
+            game.make_move(&m).map_err(|error| LoadError::IllegalMove(number, error))?;
+        }
+
+        Ok(game)
+    }
+}
+
+impl Game { // position notation
+    // Returns a single line describing the position, similar to FEN in chess - five fields separated by spaces:
+    // - the board, row by row from the top, with rows separated by '/'. Each row lists its positions from left to right,
+    //   'W' for a white piece, 'B' for a black piece, and a digit for a run of empty positions;
+    // - the side to move ('w' or 'b');
+    // - the phase ('p' for placing, 'm' for moving);
+    // - the number of unplaced pieces of white and of black.
+    // For example, the starting position is "3/3/3/6/3/3/3 w p 9 9".
+    // The history, and a mill without a removal yet, are not part of the notation.
+    pub fn to_notation(&self) -> String {
+        let layout = self.board.layout();
+        let rows: Vec<String> = layout.rows().iter().map(|row| {
+            let mut text = String::new();
+            let mut empty = 0;
+            for &i in row {
+                let symbol = match self.board.state_at(i) {
+                    State::Empty => {
+                        empty += 1;
+                        continue;
+                    }
+                    State::Occupied(Color::White) => 'W',
+                    State::Occupied(Color::Black) => 'B'
+                };
+                if empty > 0 {
+                    text += &empty.to_string();
+                    empty = 0;
+                }
+                text.push(symbol);
+            }
+            if empty > 0 {
+                text += &empty.to_string();
+            }
+            text
+        }).collect();
+
+        let current = match self.current {
+            Color::White => 'w',
+            Color::Black => 'b'
+        };
+        let phase = match self.phase {
+            Phase::Placing => 'p',
+            Phase::Moving  => 'm'
+        };
+        format!("{} {} {} {} {}", rows.join("/"), current, phase, self.white.unplaced, self.black.unplaced)
+    }
+
+    // Sets up a game at the position described by the notation returned by to_notation(), with an empty history.
+    pub fn from_notation(notation: &str, rules: Rules) -> Result<Game, NotationError> {
+        let fields: Vec<&str> = notation.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(NotationError::InvalidFormat);
+        }
+
+        let mut game = Game::new(rules);
+
+        let rows = game.board.layout().rows();
+        let texts: Vec<&str> = fields[0].split('/').collect();
+        if texts.len() != rows.len() {
+            return Err(NotationError::InvalidBoard);
+        }
+        for (row, text) in rows.iter().zip(texts) {
+            let mut positions = row.iter();
+            for symbol in text.chars() {
+                let color = match symbol {
+                    'W' => Color::White,
+                    'B' => Color::Black,
+                    '1'..='9' => {
+                        for _ in 0..symbol.to_digit(10).unwrap() {
+                            positions.next().ok_or(NotationError::InvalidBoard)?;
+                        }
+                        continue;
+                    }
+                    _ => return Err(NotationError::InvalidBoard)
+                };
+
+                let name = game.board.layout().name(*positions.next().ok_or(NotationError::InvalidBoard)?);
+                game.board.place_piece(color, name).expect("ERROR: a position is listed twice");
+                let pieces = game.pieces_mut(color);
+                pieces.placed += 1;
+                pieces.positions.insert(name.to_string());
+            }
+            if positions.next().is_some() {
+                return Err(NotationError::InvalidBoard);
+            }
+        }
+
+        let current = match fields[1] {
+            "w" => Color::White,
+            "b" => Color::Black,
+            _ => return Err(NotationError::InvalidColor)
+        };
+        let phase = match fields[2] {
+            "p" => Phase::Placing,
+            "m" => Phase::Moving,
+            _ => return Err(NotationError::InvalidPhase)
+        };
+        game.white.unplaced = fields[3].parse().map_err(|_| NotationError::InvalidCount)?;
+        game.black.unplaced = fields[4].parse().map_err(|_| NotationError::InvalidCount)?;
+
+        // summed as u16, as an unplaced count can be up to 255
+        let too_many = |pieces: &Pieces| pieces.placed as u16 + pieces.unplaced as u16 > game.rules.pieces as u16;
+        if too_many(&game.white) || too_many(&game.black) {
+            return Err(NotationError::TooManyPieces);
         }
+        // the players place in turns, so one of them can be at most one piece ahead - and then it's the other one's turn
+        // (unless they can also move instead of placing)
+        let (white, black) = (game.white.unplaced, game.black.unplaced);
+        let mismatch = (white < black && (black - white > 1 || current == Color::White)) || (black < white && (white - black > 1 || current == Color::Black));
+        if mismatch && !game.rules.interleaved {
+            return Err(NotationError::UnplacedMismatch);
+        }
+
+        game.update_phase();
+        game.set_current(current);
+        if game.phase != phase {
+            return Err(NotationError::PhaseMismatch);
+        }
+
+        Ok(game)
     }
 }
 
 
 #[cfg(test)]
+#[allow(clippy::bool_comparison)] // the tests compare with false explicitly
 mod tests {
     use super::*;
-    use crate::enums::State;
+    use crate::enums::{GameResult, WinReason, DrawReason};
+    use crate::errors::{PlacingError, MovingError, RemovingError};
+    use crate::rules::{Rules, DoubleMill, DrawRules};
 
     #[test]
     fn test_place() {
-        let mut game = Game::new();
+        let mut game = Game::default();
 
-        assert_eq!(game.place_piece(&b"a7\r\n"[..]), "a7");
+        assert_eq!(game.place_piece("a7"), Ok(()));
         game.current = game.current.other();
-        assert_eq!(game.board.get_node_ref("a7").unwrap().state, State::Occupied(Color::White));
+        assert_eq!(game.board.state("a7").unwrap(), State::Occupied(Color::White));
         
-        assert_eq!(game.place_piece(&b"a1\r\n"[..]), "a1");
+        assert_eq!(game.place_piece("a1"), Ok(()));
         game.current = game.current.other();
-        assert_eq!(game.board.get_node_ref("a1").unwrap().state, State::Occupied(Color::Black));
+        assert_eq!(game.board.state("a1").unwrap(), State::Occupied(Color::Black));
 
-        assert_eq!(game.place_piece(&b"d7\r\n"[..]), "d7");
+        assert_eq!(game.place_piece("d7"), Ok(()));
         game.current = game.current.other();
-        assert_eq!(game.board.get_node_ref("d7").unwrap().state, State::Occupied(Color::White));
+        assert_eq!(game.board.state("d7").unwrap(), State::Occupied(Color::White));
 
-        assert_eq!(game.place_piece(&b"d1\r\n"[..]), "d1");
+        assert_eq!(game.place_piece("d1"), Ok(()));
         game.current = game.current.other();
-        assert_eq!(game.board.get_node_ref("d1").unwrap().state, State::Occupied(Color::Black));
+        assert_eq!(game.board.state("d1").unwrap(), State::Occupied(Color::Black));
 
-        assert_eq!(game.place_piece(&b"g7\r\n"[..]), "g7");
+        assert_eq!(game.place_piece("g7"), Ok(()));
         game.current = game.current.other();
-        assert_eq!(game.board.get_node_ref("g7").unwrap().state, State::Occupied(Color::White));
+        assert_eq!(game.board.state("g7").unwrap(), State::Occupied(Color::White));
         assert!(game.board.in_mill(Color::White, "g7"));
+
+        assert_eq!(game.place_piece("g7"), Err(GameError::Placing(PlacingError::PlaceAtOccupied)));
+        assert_eq!(game.place_piece("a8"), Err(GameError::Placing(PlacingError::InvalidPosition)));
     }
     #[test]
     fn test_move() {
-        let mut game = Game::new();
+        let mut game = Game::default();
 
-        assert_eq!(game.place_piece(&b"a7\r\n"[..]), "a7");
+        assert_eq!(game.place_piece("a7"), Ok(()));
         game.current = game.current.other();
-        assert_eq!(game.place_piece(&b"a1\r\n"[..]), "a1");
+        assert_eq!(game.place_piece("a1"), Ok(()));
         game.current = game.current.other();
 
-        assert_eq!(game.move_piece(&b"a7a4\r\n"[..]), "a4");
+        assert_eq!(game.move_piece("a7", "a4", false), Ok(()));
         game.current = game.current.other();
-        assert_eq!(game.board.get_node_ref("a7").unwrap().state, State::Empty);
-        assert_eq!(game.board.get_node_ref("a4").unwrap().state, State::Occupied(Color::White));
+        assert_eq!(game.board.state("a7").unwrap(), State::Empty);
+        assert_eq!(game.board.state("a4").unwrap(), State::Occupied(Color::White));
 
-        assert_eq!(game.move_piece(&b"a1d1\r\n"[..]), "d1");
+        assert_eq!(game.move_piece("a1", "d1", false), Ok(()));
         game.current = game.current.other();
-        assert_eq!(game.board.get_node_ref("a1").unwrap().state, State::Empty);
-        assert_eq!(game.board.get_node_ref("d1").unwrap().state, State::Occupied(Color::Black));
+        assert_eq!(game.board.state("a1").unwrap(), State::Empty);
+        assert_eq!(game.board.state("d1").unwrap(), State::Occupied(Color::Black));
+
+        assert_eq!(game.move_piece("d1", "a1", false), Err(GameError::Moving(MovingError::MoveFromWrongColor)));
     }
     #[test]
     fn test_remove() {
-        let mut game = Game::new();
+        let mut game = Game::default();
 
-        assert_eq!(game.place_piece(&b"a7\r\n"[..]), "a7");
+        assert_eq!(game.place_piece("a7"), Ok(()));
         game.current = game.current.other();
-        assert_eq!(game.place_piece(&b"a1\r\n"[..]), "a1");
+        assert_eq!(game.place_piece("a1"), Ok(()));
         game.current = game.current.other();
 
-        assert_eq!(game.place_piece(&b"d7\r\n"[..]), "d7");
+        assert_eq!(game.place_piece("d7"), Ok(()));
         game.current = game.current.other();
-        assert_eq!(game.place_piece(&b"d1\r\n"[..]), "d1");
+        assert_eq!(game.place_piece("d1"), Ok(()));
         game.current = game.current.other();
 
-        assert_eq!(game.place_piece(&b"g7\r\n"[..]), "g7");
+        assert_eq!(game.place_piece("g7"), Ok(()));
         assert!(game.board.in_mill(Color::White, "g7"));
-        assert_eq!(game.remove_piece(&b"d1\r\n"[..]), "d1");
-        assert_eq!(game.board.get_node_ref("d1").unwrap().state, State::Empty);
+        assert_eq!(game.remove_piece("a7"), Err(GameError::Removing(RemovingError::RemoveFromWrongColor)));
+        assert_eq!(game.remove_piece("d1"), Ok(()));
+        assert_eq!(game.board.state("d1").unwrap(), State::Empty);
+        assert_eq!(game.black.placed, 1);
 
         game.current = game.current.other();
     }
 
     #[test]
     fn test_can_move() {
-        let mut game = Game::new();
+        let mut game = Game::default();
 
-        assert_eq!(game.place_piece(&b"a1\r\n"[..]), "a1");
-        assert_eq!(game.place_piece(&b"a4\r\n"[..]), "a4");
-        assert_eq!(game.place_piece(&b"a7\r\n"[..]), "a7");
-        assert_eq!(game.place_piece(&b"b4\r\n"[..]), "b4");
+        assert_eq!(game.place_piece("a1"), Ok(()));
+        assert_eq!(game.place_piece("a4"), Ok(()));
+        assert_eq!(game.place_piece("a7"), Ok(()));
+        assert_eq!(game.place_piece("b4"), Ok(()));
 
         game.current = game.current.other();
-        assert_eq!(game.place_piece(&b"d1\r\n"[..]), "d1");
-        assert_eq!(game.place_piece(&b"d7\r\n"[..]), "d7");
-        assert_eq!(game.place_piece(&b"b2\r\n"[..]), "b2");
-        assert_eq!(game.place_piece(&b"b6\r\n"[..]), "b6");
-        assert_eq!(game.place_piece(&b"c4\r\n"[..]), "c4");
+        assert_eq!(game.place_piece("d1"), Ok(()));
+        assert_eq!(game.place_piece("d7"), Ok(()));
+        assert_eq!(game.place_piece("b2"), Ok(()));
+        assert_eq!(game.place_piece("b6"), Ok(()));
+        assert_eq!(game.place_piece("c4"), Ok(()));
 
         assert!(game.can_move(Color::White) == false);
         assert!(game.can_move(Color::Black));
     }
     #[test]
     fn test_can_move_flying() {
-        let mut game = Game::new();
+        let mut game = Game::default();
 
-        assert_eq!(game.place_piece(&b"a1\r\n"[..]), "a1");
-        assert_eq!(game.place_piece(&b"a4\r\n"[..]), "a4");
-        assert_eq!(game.place_piece(&b"a7\r\n"[..]), "a7");
+        assert_eq!(game.place_piece("a1"), Ok(()));
+        assert_eq!(game.place_piece("a4"), Ok(()));
+        assert_eq!(game.place_piece("a7"), Ok(()));
 
         game.current = game.current.other();
-        assert_eq!(game.place_piece(&b"d1\r\n"[..]), "d1");
-        assert_eq!(game.place_piece(&b"b4\r\n"[..]), "b4");
-        assert_eq!(game.place_piece(&b"d7\r\n"[..]), "d7");
+        assert_eq!(game.place_piece("d1"), Ok(()));
+        assert_eq!(game.place_piece("b4"), Ok(()));
+        assert_eq!(game.place_piece("d7"), Ok(()));
 
         assert!(game.can_move(Color::White));
         assert!(game.can_move(Color::Black));
     }
     #[test]
     fn test_can_play() {
-        let mut game = Game::new();
+        let mut game = Game::default();
 
-        assert_eq!(game.place_piece(&b"a1\r\n"[..]), "a1");
-        assert_eq!(game.place_piece(&b"a4\r\n"[..]), "a4");
-        assert_eq!(game.place_piece(&b"a7\r\n"[..]), "a7");
+        assert_eq!(game.place_piece("a1"), Ok(()));
+        assert_eq!(game.place_piece("a4"), Ok(()));
+        assert_eq!(game.place_piece("a7"), Ok(()));
 
         game.current = game.current.other();
-        assert_eq!(game.place_piece(&b"d1\r\n"[..]), "d1");
-        assert_eq!(game.place_piece(&b"b4\r\n"[..]), "b4");
-        assert_eq!(game.place_piece(&b"d7\r\n"[..]), "d7");
+        assert_eq!(game.place_piece("d1"), Ok(()));
+        assert_eq!(game.place_piece("b4"), Ok(()));
+        assert_eq!(game.place_piece("d7"), Ok(()));
 
         assert!(game.can_play(Color::White));
         assert!(game.can_play(Color::Black));
 
         game.current = game.current.other();
-        assert_eq!(game.remove_piece(&b"b4\r\n"[..]), "b4");
+        assert_eq!(game.remove_piece("b4"), Ok(()));
 
         assert!(game.can_play(Color::Black) == false);
     }
+
+    #[test]
+    fn test_apply() {
+        let mut game = Game::default();
+
+        assert_eq!(game.apply(Action::Place("a7".to_string())), Ok(()));
+        assert_eq!(game.current(), Color::Black);
+        assert_eq!(game.apply(Action::Place("a7".to_string())), Err(GameError::Placing(PlacingError::PlaceAtOccupied)));
+        assert_eq!(game.current(), Color::Black);
+        assert_eq!(game.apply(Action::Move("a1".to_string(), "a4".to_string())), Err(GameError::WrongPhase));
+        assert_eq!(game.apply(Action::Remove("a7".to_string())), Err(GameError::RemovalUnexpected));
+
+        assert_eq!(game.apply(Action::Place("a1".to_string())), Ok(()));
+        assert_eq!(game.apply(Action::Place("d7".to_string())), Ok(()));
+        assert_eq!(game.apply(Action::Place("d1".to_string())), Ok(()));
+        assert_eq!(game.apply(Action::Place("g7".to_string())), Ok(()));
+
+        // white formed a mill, so it's still their turn until they remove a piece
+        assert!(game.removal_pending());
+        assert_eq!(game.current(), Color::White);
+        assert_eq!(game.apply(Action::Place("g1".to_string())), Err(GameError::RemovalExpected));
+        assert_eq!(game.apply(Action::Remove("g7".to_string())), Err(GameError::Removing(RemovingError::RemoveFromWrongColor)));
+        assert_eq!(game.apply(Action::Remove("d1".to_string())), Ok(()));
+
+        assert!(!game.removal_pending());
+        assert_eq!(game.current(), Color::Black);
+        assert_eq!(game.pieces(Color::Black).placed, 1);
+        assert_eq!(game.board().state("d1").unwrap(), State::Empty);
+    }
+    #[test]
+    fn test_apply_phases() {
+        let mut game = Game::default();
+
+        // no mills are formed by these placements
+        let placements = ["a7", "d7", "g7", "d6", "b6", "d1",
+                          "f6", "a4", "c5", "g4", "e5", "b4",
+                          "a1", "f4", "g1", "c3", "d2", "e3"];
+        for position in placements.iter() {
+            assert_eq!(game.phase(), Phase::Placing);
+            assert_eq!(game.apply(Action::Place(position.to_string())), Ok(()));
+        }
+
+        assert_eq!(game.phase(), Phase::Moving);
+        assert_eq!(game.apply(Action::Place("c4".to_string())), Err(GameError::WrongPhase));
+        assert_eq!(game.apply(Action::Move("d2".to_string(), "e4".to_string())), Err(GameError::Moving(MovingError::NotAdjacent)));
+        assert_eq!(game.apply(Action::Move("d2".to_string(), "b2".to_string())), Ok(()));
+        assert_eq!(game.current(), Color::Black);
+    }
+
+    fn play(game: &mut Game, moves: &[&str]) {
+        for m in moves {
+            assert_eq!(game.make_move(&m.parse().unwrap()), Ok(()), "{}", m);
+        }
+    }
+
+    #[test]
+    fn test_make_move() {
+        let mut game = Game::default();
+        play(&mut game, &["a7", "a1", "d7", "d1"]);
+
+        // a mill without a capture and a capture without a mill are both rejected, leaving the game unchanged
+        assert_eq!(game.make_move(&"g7".parse().unwrap()), Err(GameError::RemovalExpected));
+        assert_eq!(game.make_move(&"g4xd1".parse().unwrap()), Err(GameError::RemovalUnexpected));
+        assert_eq!(game.make_move(&"g7xd7".parse().unwrap()), Err(GameError::Removing(RemovingError::RemoveFromWrongColor)));
+        assert_eq!(game.board.state("g7").unwrap(), State::Empty);
+        assert_eq!(game.board.state("g4").unwrap(), State::Empty);
+        assert_eq!(game.current(), Color::White);
+        assert_eq!(game.pieces(Color::White).unplaced, 7);
+
+        assert_eq!(game.make_move(&"g7xd1".parse().unwrap()), Ok(()));
+        assert_eq!(game.board.state("g7").unwrap(), State::Occupied(Color::White));
+        assert_eq!(game.board.state("d1").unwrap(), State::Empty);
+        assert_eq!(game.current(), Color::Black);
+        assert_eq!(game.pieces(Color::Black).placed, 1);
+
+        assert_eq!(game.make_move(&"a1-a4".parse().unwrap()), Err(GameError::WrongPhase));
+    }
+    #[test]
+    fn test_make_move_moving() {
+        let mut game = Game::default();
+        play(&mut game, &["a7", "d7", "g7", "d6", "b6", "d1",
+                          "f6", "a4", "c5", "g4", "e5", "b4",
+                          "a1", "f4", "g1", "c3", "d2", "e3"]);
+
+        assert_eq!(game.make_move(&"d2-e4".parse().unwrap()), Err(GameError::Moving(MovingError::NotAdjacent)));
+        assert_eq!(game.make_move(&"d2*e4".parse().unwrap()), Err(GameError::FlyingNotAllowed));
+        assert_eq!(game.parse_move("d2e4"), Ok(Move::step("d2".parse().unwrap(), "e4".parse().unwrap())));
+
+        play(&mut game, &["d2-b2", "d6-d5", "b2-d2", "e3-d3"]);
+        assert_eq!(game.current(), Color::White);
+        assert_eq!(game.pieces(Color::White).positions.len(), 9);
+        assert_eq!(game.board.state("d5").unwrap(), State::Occupied(Color::Black));
+        assert_eq!(game.board.state("d3").unwrap(), State::Occupied(Color::Black));
+    }
+    #[test]
+    fn test_parse_move_flying() {
+        let mut game = Game::default();
+        play(&mut game, &["a7", "d7", "g7", "d6", "b6", "d1",
+                          "f6", "a4", "c5", "g4", "e5", "b4",
+                          "a1", "f4", "g1", "c3", "d2", "e3"]);
+
+        for position in ["a7", "g7", "b6", "f6", "c5", "e5"].iter() {
+            game.board.remove_piece(Color::White, position, false).unwrap();
+            game.white.remove_piece(position.to_string());
+        }
+
+        assert!(game.flying());
+        assert_eq!(game.parse_move("d2e4"), Ok(Move::fly("d2".parse().unwrap(), "e4".parse().unwrap())));
+        assert_eq!(game.parse_move("d2-b2"), Ok(Move::fly("d2".parse().unwrap(), "b2".parse().unwrap())));
+        assert!(game.legal_moves().contains(&game.parse_move("d2-b2").unwrap()));
+        assert_eq!(game.make_move(&game.parse_move("d2e4").unwrap()), Ok(()));
+        assert_eq!(game.board.state("e4").unwrap(), State::Occupied(Color::White));
+    }
+
+    #[test]
+    fn test_legal_moves_placing() {
+        let mut game = Game::default();
+        assert_eq!(game.legal_moves().len(), 24);
+        assert_eq!(game.legal_moves()[0], "a1".parse().unwrap());
+
+        play(&mut game, &["a7", "a1", "d7", "d1"]);
+        let moves = game.legal_moves();
+        assert_eq!(moves.len(), 20 - 1 + 2);
+        assert!(moves.contains(&"g7xa1".parse().unwrap()));
+        assert!(moves.contains(&"g7xd1".parse().unwrap()));
+        assert!(!moves.contains(&"g7".parse().unwrap()));
+        assert!(moves.contains(&"g4".parse().unwrap()));
+
+        for m in moves {
+            assert_eq!(game.clone().make_move(&m), Ok(()), "{}", m);
+        }
+    }
+    #[test]
+    fn test_legal_moves_mills() {
+        let mut game = Game::default();
+        play(&mut game, &["a7", "a1", "d7", "d1", "b6", "g1xd7"]);
+
+        // black's a1-d1-g1 mill is protected, so white can only capture outside of it
+        play(&mut game, &["d6", "b4"]);
+        let captures: Vec<Move> = game.legal_moves().into_iter().filter(|m| m.to() == "f6".parse().unwrap()).collect();
+        assert_eq!(captures, vec!["f6xb4".parse().unwrap()]);
+
+        // if all of black's pieces are in mills, any of them can be captured
+        game.board.remove_piece(Color::Black, "b4", false).unwrap();
+        game.black.remove_piece("b4".to_string());
+        let captures: Vec<Move> = game.legal_moves().into_iter().filter(|m| m.to() == "f6".parse().unwrap()).collect();
+        assert_eq!(captures, vec!["f6xa1".parse().unwrap(), "f6xd1".parse().unwrap(), "f6xg1".parse().unwrap()]);
+    }
+    #[test]
+    fn test_legal_moves_moving() {
+        let mut game = Game::default();
+        play(&mut game, &["a7", "d7", "g7", "d6", "b6", "d1",
+                          "f6", "a4", "c5", "g4", "e5", "b4",
+                          "a1", "f4", "g1", "c3", "d2", "e3"]);
+
+        let moves: Vec<String> = game.legal_moves().iter().map(|m| m.to_string()).collect();
+        assert_eq!(moves, ["c5-c4", "c5-d5", "d2-b2", "d2-d3", "d2-f2", "e5-d5", "e5-e4"]);
+
+        for position in ["a7", "g7", "b6", "f6", "c5", "e5"].iter() {
+            game.board.remove_piece(Color::White, position, false).unwrap();
+            game.white.remove_piece(position.to_string());
+        }
+
+        // with 3 pieces left, white can fly to any of the empty positions
+        let moves = game.legal_moves();
+        assert!(moves.iter().all(|m| matches!(m.kind, MoveKind::Fly(_, _))));
+        assert!(moves.contains(&"d2*f6".parse().unwrap()));
+        assert_eq!(moves.iter().filter(|m| m.capture.is_none()).count(), 3 * 12);
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut game = Game::default();
+        assert_eq!(game.undo(), Err(GameError::NothingToUndo));
+        assert_eq!(game.redo(), Err(GameError::NothingToRedo));
+
+        play(&mut game, &["a7", "a1", "d7", "d1", "g7xd1"]);
+        assert_eq!(game.history().len(), 5);
+
+        assert_eq!(game.undo(), Ok(()));
+        assert_eq!(game.current(), Color::White);
+        assert_eq!(game.board.state("g7"), Some(State::Empty));
+        assert_eq!(game.board.state("d1"), Some(State::Occupied(Color::Black)));
+        assert_eq!((game.white.unplaced, game.white.placed), (7, 2));
+        assert_eq!((game.black.unplaced, game.black.placed), (7, 2));
+        assert!(game.black.positions.contains("d1"));
+        assert!(!game.white.positions.contains("g7"));
+
+        assert_eq!(game.undo(), Ok(()));
+        assert_eq!(game.current(), Color::Black);
+        assert_eq!(game.board.state("d1"), Some(State::Empty));
+        assert_eq!((game.black.unplaced, game.black.placed), (8, 1));
+
+        assert_eq!(game.redo(), Ok(()));
+        assert_eq!(game.redo(), Ok(()));
+        assert_eq!(game.redo(), Err(GameError::NothingToRedo));
+        assert_eq!(game.history(), &play_moves(&["a7", "a1", "d7", "d1", "g7xd1"])[..]);
+        assert_eq!(game.board.state("g7"), Some(State::Occupied(Color::White)));
+        assert_eq!(game.board.state("d1"), Some(State::Empty));
+        assert_eq!(game.current(), Color::Black);
+
+        // a new move makes the undone moves unreachable
+        assert_eq!(game.undo(), Ok(()));
+        play(&mut game, &["g4"]);
+        assert_eq!(game.redo(), Err(GameError::NothingToRedo));
+    }
+    #[test]
+    fn test_undo_pending_removal() {
+        let mut game = Game::default();
+        play(&mut game, &["a7", "a1", "d7", "d1"]);
+
+        assert_eq!(game.apply(Action::Place("g7".to_string())), Ok(()));
+        assert!(game.removal_pending());
+        assert_eq!(game.undo(), Ok(()));
+        assert!(!game.removal_pending());
+        assert_eq!(game.current(), Color::White);
+        assert_eq!(game.board.state("g7"), Some(State::Empty));
+        assert_eq!(game.white.unplaced, 7);
+        assert_eq!(game.history().len(), 4);
+
+        assert_eq!(game.apply(Action::Place("g7".to_string())), Ok(()));
+        assert_eq!(game.apply(Action::Remove("a1".to_string())), Ok(()));
+        assert_eq!(game.history().last(), Some(&"g7xa1".parse().unwrap()));
+    }
+    #[test]
+    fn test_undo_phases() {
+        let mut game = Game::default();
+        let placements = ["a7", "d7", "g7", "d6", "b6", "d1",
+                          "f6", "a4", "c5", "g4", "e5", "b4",
+                          "a1", "f4", "g1", "c3", "d2", "e3"];
+        play(&mut game, &placements);
+        play(&mut game, &["d2-b2", "d6-d5"]);
+        assert_eq!(game.phase(), Phase::Moving);
+
+        assert_eq!(game.undo(), Ok(()));
+        assert_eq!(game.undo(), Ok(()));
+        assert_eq!(game.phase(), Phase::Moving);
+        assert_eq!(game.board.state("d2"), Some(State::Occupied(Color::White)));
+        assert_eq!(game.board.state("b2"), Some(State::Empty));
+        assert!(game.white.positions.contains("d2"));
+
+        assert_eq!(game.undo(), Ok(()));
+        assert_eq!(game.phase(), Phase::Placing);
+        assert_eq!(game.current(), Color::Black);
+        assert_eq!(game.black.unplaced, 1);
+
+        while game.undo().is_ok() {}
+        assert_eq!(game.current(), Color::White);
+        assert!(game.board.positions().iter().all(|position| game.board.state(position) == Some(State::Empty)));
+        assert_eq!((game.white.unplaced, game.white.placed), (9, 0));
+        assert_eq!((game.black.unplaced, game.black.placed), (9, 0));
+
+        while game.redo().is_ok() {}
+        assert_eq!(game.history().len(), placements.len() + 2);
+        assert_eq!(game.board.state("d5"), Some(State::Occupied(Color::Black)));
+    }
+
+    fn play_moves(moves: &[&str]) -> Vec<Move> {
+        moves.iter().map(|m| m.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_outcome() {
+        let mut game = Game::default();
+        let placements = ["a7", "d7", "g7", "d6", "b6", "d1",
+                          "f6", "a4", "c5", "g4", "e5", "b4",
+                          "a1", "f4", "g1", "c3", "d2", "e3"];
+        play(&mut game, &placements[..17]);
+        assert_eq!(game.outcome(), None);
+        play(&mut game, &placements[17..]);
+        assert_eq!(game.outcome(), None);
+
+        let mut few_pieces = game.clone();
+        for position in ["d7", "d6", "d1", "a4", "g4", "b4", "f4"].iter() {
+            few_pieces.board.remove_piece(Color::Black, position, false).unwrap();
+            few_pieces.black.remove_piece(position.to_string());
+        }
+        assert_eq!(few_pieces.outcome(), Some(GameResult::Win { winner: Color::White, reason: WinReason::TooFewPieces }));
+        assert!(few_pieces.is_over());
+        assert_eq!(few_pieces.make_move(&"d2-b2".parse().unwrap()), Err(GameError::GameOver));
+        assert!(few_pieces.legal_moves().is_empty());
+    }
+    // sets up a game in the moving phase with pieces at the given positions
+    fn setup(white: &[&str], black: &[&str]) -> Game {
+        let mut game = Game::default();
+        for (color, positions) in [(Color::White, white), (Color::Black, black)].iter() {
+            for position in positions.iter() {
+                game.board.place_piece(*color, position).unwrap();
+                game.pieces_mut(*color).place_piece(position.to_string());
+            }
+            game.pieces_mut(*color).unplaced = 0;
+        }
+        game.phase = Phase::Moving;
+        game.positions = vec![game.hash()];
+
+        game
+    }
+
+    #[test]
+    fn test_outcome_blocked() {
+        // white has a mill in the a column, but all of its pieces are blocked
+        let game = setup(&["a1", "a4", "a7", "b4"], &["d1", "d7", "b6", "c4", "b2"]);
+        assert_eq!(game.outcome(), Some(GameResult::Win { winner: Color::Black, reason: WinReason::Blocked }));
+        assert!(game.legal_moves().is_empty());
+
+        // with only 3 pieces, white can fly, so it isn't blocked
+        let game = setup(&["a1", "a4", "a7"], &["d1", "d7", "b4"]);
+        assert_eq!(game.outcome(), None);
+    }
+    #[test]
+    fn test_resign() {
+        let mut game = Game::default();
+        play(&mut game, &["a7", "a1"]);
+
+        assert_eq!(game.resign(Color::White), Ok(()));
+        assert_eq!(game.outcome(), Some(GameResult::Win { winner: Color::Black, reason: WinReason::Resignation }));
+        assert_eq!(game.timeout(Color::Black), Err(GameError::GameOver));
+        assert_eq!(game.make_move(&"d7".parse().unwrap()), Err(GameError::GameOver));
+        assert_eq!(game.apply(Action::Place("d7".to_string())), Err(GameError::GameOver));
+
+        let mut game = Game::default();
+        assert_eq!(game.timeout(Color::Black), Ok(()));
+        assert_eq!(game.outcome(), Some(GameResult::Win { winner: Color::White, reason: WinReason::Timeout }));
+    }
+    #[test]
+    fn test_result_display() {
+        assert_eq!(GameResult::Win { winner: Color::Black, reason: WinReason::TooFewPieces }.to_string(),
+                   "VICTORY for BLACK player - white player has less than 3 pieces!");
+        assert_eq!(GameResult::Win { winner: Color::White, reason: WinReason::Blocked }.to_string(),
+                   "VICTORY for WHITE player - black player cannot move their pieces!");
+        assert_eq!(GameResult::Draw { reason: DrawReason::BothBlocked }.to_string(),
+                   "DRAW - neither player can move their pieces!");
+    }
+
+    #[test]
+    fn test_save_load() {
+        let mut game = Game::default();
+        game.set_current(Color::Black);
+        play(&mut game, &["a7", "d7", "g7", "d6", "b6", "d1",
+                          "f6", "a4", "c5", "g4", "e5", "b4",
+                          "a1", "f4", "g1", "c3", "d2", "e3", "d2-d3"]);
+
+        let text = game.save();
+        assert!(text.starts_with("first: black\na7\nd7\n"));
+        assert!(text.ends_with("e3\nd2-d3\n"));
+
+        let loaded = Game::load(&text, Rules::default()).unwrap();
+        assert_eq!(loaded.save(), text);
+        assert_eq!(loaded.first(), Color::Black);
+        assert_eq!(loaded.current(), game.current());
+        assert_eq!(loaded.phase(), Phase::Moving);
+        assert_eq!(loaded.board().positions(), game.board().positions());
+        assert_eq!(loaded.pieces(Color::White).positions, game.pieces(Color::White).positions);
+
+        let mut game = Game::default();
+        play(&mut game, &["a7", "a1", "d7", "d1", "g7xd1"]);
+        game.resign(Color::Black).unwrap();
+        let text = game.save();
+        assert_eq!(text, "first: white\na7\na1\nd7\nd1\ng7xd1\nresigned: black\n");
+        assert_eq!(Game::load(&text, Rules::default()).unwrap().outcome(), game.outcome());
+    }
+    #[test]
+    fn test_load_errors() {
+        assert_eq!(Game::load("", Rules::default()).err(), Some(LoadError::MissingFirst));
+        assert_eq!(Game::load("a7\n", Rules::default()).err(), Some(LoadError::MissingFirst));
+        assert_eq!(Game::load("first: red\n", Rules::default()).err(), Some(LoadError::MissingFirst));
+        assert_eq!(Game::load("# comment\n\nfirst: white\na7\n\na-7\n", Rules::default()).err(), Some(LoadError::InvalidMove(6)));
+        assert_eq!(Game::load("first: white\na7\na7\n", Rules::default()).err(),
+                   Some(LoadError::IllegalMove(3, GameError::Placing(PlacingError::PlaceAtOccupied))));
+        assert_eq!(Game::load("first: white\na7\nresigned: blue\n", Rules::default()).err(), Some(LoadError::InvalidResult(3)));
+        assert_eq!(Game::load("first: white\nresigned: white\na7\n", Rules::default()).err(), Some(LoadError::InvalidResult(3)));
+    }
+
+    #[test]
+    fn test_notation() {
+        let mut game = Game::default();
+        assert_eq!(game.to_notation(), "3/3/3/6/3/3/3 w p 9 9");
+        assert_eq!(Game::from_notation("3/3/3/6/3/3/3 w p 9 9", Rules::default()).unwrap().to_notation(), game.to_notation());
+
+        play(&mut game, &["a7", "a1", "d7", "d1", "g7xd1", "d1"]);
+        let notation = game.to_notation();
+        assert_eq!(notation, "WWW/3/3/6/3/3/BB1 w p 6 6");
+
+        let loaded = Game::from_notation(&notation, Rules::default()).unwrap();
+        assert_eq!(loaded.to_notation(), notation);
+        assert_eq!(loaded.current(), Color::White);
+        assert_eq!(loaded.pieces(Color::Black).placed, 2);
+        assert_eq!(loaded.pieces(Color::White).positions, game.pieces(Color::White).positions);
+        assert_eq!(loaded.legal_moves(), game.legal_moves());
+        assert!(loaded.history().is_empty());
+
+        let game = Game::from_notation("W2/3/3/6/3/3/BB1 b m 0 0", Rules::default()).unwrap();
+        assert_eq!(game.phase(), Phase::Moving);
+        assert_eq!(game.outcome(), Some(GameResult::Win { winner: Color::Black, reason: WinReason::TooFewPieces }));
+    }
+    #[test]
+    fn test_pieces_fit() {
+        // 9 pieces each would fill the board of three men's morris in the middle of the placing phase, leaving no move
+        let rules = Rules { pieces: 9, ..Rules::three_mens_morris() };
+        assert!(!rules.fits());
+        assert!(std::panic::catch_unwind(|| Game::new(rules)).is_err());
+
+        // twelve pieces each fill the board of twelve men's morris exactly, by the last placement
+        assert!(Rules::twelve_mens_morris().fits());
+        assert!(Rules { pieces: 4, ..Rules::three_mens_morris() }.fits());
+        assert!(!Rules { pieces: 5, ..Rules::three_mens_morris() }.fits());
+    }
+
+    #[test]
+    fn test_notation_errors() {
+        assert_eq!(Game::from_notation("3/3/3/6/3/3/3 w p 9", Rules::default()).err(), Some(NotationError::InvalidFormat));
+        assert_eq!(Game::from_notation("3/3/3/6/3/3 w p 9 9", Rules::default()).err(), Some(NotationError::InvalidBoard));
+        assert_eq!(Game::from_notation("3/3/3/7/3/3/3 w p 9 9", Rules::default()).err(), Some(NotationError::InvalidBoard));
+        assert_eq!(Game::from_notation("3/3/3/5/3/3/3 w p 9 9", Rules::default()).err(), Some(NotationError::InvalidBoard));
+        assert_eq!(Game::from_notation("3/3/3/6/3/3/2X w p 9 9", Rules::default()).err(), Some(NotationError::InvalidBoard));
+        assert_eq!(Game::from_notation("3/3/3/6/3/3/3 x p 9 9", Rules::default()).err(), Some(NotationError::InvalidColor));
+        assert_eq!(Game::from_notation("3/3/3/6/3/3/3 w x 9 9", Rules::default()).err(), Some(NotationError::InvalidPhase));
+        assert_eq!(Game::from_notation("3/3/3/6/3/3/3 w p 9 -1", Rules::default()).err(), Some(NotationError::InvalidCount));
+        assert_eq!(Game::from_notation("W2/3/3/6/3/3/3 w p 9 9", Rules::default()).err(), Some(NotationError::TooManyPieces));
+        assert_eq!(Game::from_notation("W2/3/3/6/3/3/3 w p 255 9", Rules::default()).err(), Some(NotationError::TooManyPieces));
+        assert_eq!(Game::from_notation("3/3/3/6/3/3/3 w p 9 255", Rules::default()).err(), Some(NotationError::TooManyPieces));
+        assert_eq!(Game::from_notation("W2/3/3/6/3/3/3 w p 8 9", Rules::default()).err(), Some(NotationError::UnplacedMismatch));
+        assert_eq!(Game::from_notation("W2/3/3/6/3/3/3 b p 8 6", Rules::default()).err(), Some(NotationError::UnplacedMismatch));
+        assert_eq!(Game::from_notation("W2/3/3/6/3/3/3 b m 8 9", Rules::default()).err(), Some(NotationError::PhaseMismatch));
+        assert_eq!(Game::from_notation("3/3/3/6/3/3/3 w p 0 0", Rules::default()).err(), Some(NotationError::PhaseMismatch));
+    }
+
+    #[test]
+    fn test_repetition() {
+        let mut game = setup(&["a1", "a4", "g7", "d2"], &["g1", "g4", "a7", "d6"]);
+        assert_eq!(game.repetitions(), 1);
+
+        let shuffle = ["d2-d3", "d6-d5", "d3-d2", "d5-d6"];
+        play(&mut game, &shuffle);
+        assert_eq!(game.repetitions(), 2);
+        assert_eq!(game.outcome(), None);
+
+        play(&mut game, &shuffle[..3]);
+        assert_eq!(game.outcome(), None);
+        play(&mut game, &shuffle[3..]);
+        assert_eq!(game.repetitions(), 3);
+        assert_eq!(game.outcome(), Some(GameResult::Draw { reason: DrawReason::Repetition(3) }));
+        assert_eq!(game.make_move(&"d2-d3".parse().unwrap()), Err(GameError::GameOver));
+
+        game.undo().unwrap();
+        assert_eq!(game.outcome(), None);
+        game.set_draw_rules(DrawRules { repetitions: Some(4), ..DrawRules::default() });
+        game.redo().unwrap();
+        assert_eq!(game.outcome(), None);
+        game.set_draw_rules(DrawRules::none());
+        play(&mut game, &shuffle);
+        assert_eq!(game.outcome(), None);
+    }
+    #[test]
+    fn test_moves_without_mill() {
+        let mut game = setup(&["a1", "a4", "g7", "d2"], &["g1", "g4", "a7", "d6"]);
+        game.set_draw_rules(DrawRules { repetitions: None, moves_without_mill: Some(6) });
+
+        play(&mut game, &["d2-d3", "d6-d5", "d3-d2", "d5-d6", "a1-d1"]);
+        assert_eq!(game.moves_without_mill(), 5);
+        assert_eq!(game.outcome(), None);
+        play(&mut game, &["g4-f4"]);
+        assert_eq!(game.outcome(), Some(GameResult::Draw { reason: DrawReason::NoMills(6) }));
+
+        // a mill starts the count again
+        let mut game = Game::default();
+        game.set_draw_rules(DrawRules { repetitions: None, moves_without_mill: Some(2) });
+        play(&mut game, &["a7", "d7", "g7", "d6", "b6", "d1",
+                          "f6", "a4", "c5", "g4", "e5", "b4",
+                          "a1", "f4", "g1", "c3", "d2", "e3"]);
+        assert_eq!(game.moves_without_mill(), 0);
+        play(&mut game, &["d2-d3"]);
+        assert_eq!(game.outcome(), None);
+    }
+    #[test]
+    fn test_draw_display() {
+        assert_eq!(GameResult::Draw { reason: DrawReason::Repetition(3) }.to_string(), "DRAW - the same position occurred 3 times!");
+        assert_eq!(GameResult::Draw { reason: DrawReason::NoMills(50) }.to_string(), "DRAW - no mill was formed in the last 50 moves!");
+    }
+
+    #[test]
+    fn test_rules_pieces() {
+        let mut game = Game::new(Rules { pieces: 4, ..Rules::default() });
+        assert_eq!(game.pieces(Color::White).unplaced, 4);
+
+        play(&mut game, &["a7", "a1", "d7", "d1", "a4", "g4", "b6", "b2"]);
+        assert_eq!(game.phase(), Phase::Moving);
+        assert_eq!(game.to_notation(), "WW1/W2/3/W4B/3/B2/BB1 w m 0 0");
+        assert_eq!(Game::from_notation("3/3/3/6/3/3/3 w p 10 10", Rules::default()).err(), Some(NotationError::TooManyPieces));
+    }
+    #[test]
+    fn test_rules_flying() {
+        let mut game = setup(&["a1", "a4", "g7"], &["g1", "g4", "a7", "d6"]);
+        game.rules.flying = false;
+
+        assert!(!game.flying());
+        assert_eq!(game.make_move(&"g7*d2".parse().unwrap()), Err(GameError::FlyingNotAllowed));
+        assert_eq!(game.parse_move("g7d7"), Ok("g7-d7".parse().unwrap()));
+        assert!(game.legal_moves().iter().all(|m| matches!(m.kind, MoveKind::Step(_, _))));
+
+        // without flying, a player with 3 pieces can be blocked
+        let mut game = setup(&["a1", "a4", "a7"], &["d1", "d7", "b4", "g1"]);
+        game.rules.flying = false;
+        assert_eq!(game.outcome(), Some(GameResult::Win { winner: Color::Black, reason: WinReason::Blocked }));
+    }
+    #[test]
+    fn test_rules_protect_mills() {
+        // black has a mill in the bottom row, and white forms one by placing at g7
+        let mut game = Game::from_notation("WW1/3/3/6/3/B2/BBB w p 5 5", Rules::default()).unwrap();
+        let captures = |game: &Game| game.legal_moves().iter().filter(|m| m.to().to_string() == "g7").count();
+        assert_eq!(captures(&game), 1);
+        assert_eq!(game.make_move(&"g7xd1".parse().unwrap()), Err(GameError::Removing(RemovingError::RemoveFromMill)));
+
+        game.rules.protect_mills = false;
+        assert_eq!(captures(&game), 4);
+        assert_eq!(game.make_move(&"g7xd1".parse().unwrap()), Ok(()));
+    }
+    #[test]
+    fn test_rules_double_mill() {
+        let moves = ["a7", "d7", "a1", "d1", "b4", "g7", "c4", "g1"];
+
+        // by default, forming two mills at once only captures one piece
+        let mut game = Game::default();
+        play(&mut game, &moves);
+        assert_eq!(game.legal_moves().iter().filter(|m| m.to().to_string() == "a4").count(), 4);
+        assert_eq!(game.make_move(&"a4xd7xg7".parse().unwrap()), Err(GameError::RemovalUnexpected));
+        assert_eq!(game.make_move(&"a4xd7".parse().unwrap()), Ok(()));
+
+        let mut game = Game::new(Rules { double_mill: DoubleMill::TwoCaptures, ..Rules::default() });
+        play(&mut game, &moves);
+        let double: Vec<Move> = game.legal_moves().into_iter().filter(|m| m.to().to_string() == "a4").collect();
+        assert_eq!(double.len(), 6);
+        assert!(double.iter().all(|m| m.second_capture.is_some()));
+        assert_eq!(game.make_move(&"a4xd7".parse().unwrap()), Err(GameError::RemovalExpected));
+        assert_eq!(game.board().state("d7").unwrap(), State::Occupied(Color::Black));
+
+        // step by step, the turn only ends after the second removal
+        game.apply(Action::Place("a4".to_string())).unwrap();
+        game.apply(Action::Remove("d7".to_string())).unwrap();
+        assert!(game.removal_pending());
+        game.apply(Action::Remove("g7".to_string())).unwrap();
+        assert!(!game.removal_pending());
+        assert_eq!(game.history().last(), Some(&"a4xd7xg7".parse().unwrap()));
+        assert_eq!(game.pieces(Color::Black).placed, 2);
+
+        game.undo().unwrap();
+        assert_eq!(game.pieces(Color::Black).placed, 4);
+        assert_eq!(game.board().state("g7").unwrap(), State::Occupied(Color::Black));
+        assert_eq!(game.make_move(&"a4xg7xd7".parse().unwrap()), Ok(()));
+    }
+
+    #[test]
+    fn test_twelve_mens_morris() {
+        let mut game = Game::new(Rules::twelve_mens_morris());
+        assert_eq!(game.pieces(Color::White).unplaced, 12);
+
+        // a diagonal line forms a mill
+        play(&mut game, &["a7", "d7", "b6", "d1"]);
+        assert!(game.legal_moves().iter().filter(|m| m.to().to_string() == "c5").all(|m| m.capture.is_some()));
+        assert_eq!(game.make_move(&"c5".parse().unwrap()), Err(GameError::RemovalExpected));
+        play(&mut game, &["c5xd7"]);
+        assert_eq!(game.pieces(Color::Black).placed, 1);
+
+        // diagonal neighbours can be moved to
+        let mut game = Game::from_notation("W2/3/3/3WW1/3/B2/BB1 w m 0 0", Rules::twelve_mens_morris()).unwrap();
+        game.rules.flying = false;
+        let moves: Vec<String> = game.legal_moves().iter().filter(|m| m.from().unwrap().to_string() == "a7").map(|m| m.to_string()).collect();
+        assert_eq!(moves, ["a7-a4", "a7-b6", "a7-d7"]);
+    }
+    #[test]
+    fn test_full_board() {
+        // with 12 pieces each, the board can be filled - then neither player can move
+        let game = Game::from_notation("WBW/BWB/BWB/WBWBWB/BWB/WBW/WBW w m 0 0", Rules::twelve_mens_morris()).unwrap();
+        assert_eq!(game.board().empty(), 0);
+        assert_eq!(game.outcome(), Some(GameResult::Draw { reason: DrawReason::BothBlocked }));
+    }
+
+    #[test]
+    fn test_three_mens_morris() {
+        let mut game = Game::new(Rules::three_mens_morris());
+        assert_eq!(game.to_notation(), "3/3/3 w p 3 3");
+
+        // forming a mill leaves black with too few pieces
+        play(&mut game, &["a3", "a1", "b2", "c3", "c1xa1"]);
+        assert_eq!(game.outcome(), None);
+        play(&mut game, &["b1"]);
+        assert_eq!(game.outcome(), Some(GameResult::Win { winner: Color::White, reason: WinReason::TooFewPieces }));
+
+        // no flying with 3 pieces, but the centre is connected to every position
+        let game = Game::from_notation("WB1/1W1/BWB w m 0 0", Rules::three_mens_morris()).unwrap();
+        let moves: Vec<String> = game.legal_moves().iter().map(|m| m.to_string()).collect();
+        assert_eq!(moves, ["a3-a2", "b2-a2", "b2-c2", "b2-c3"]);
+    }
+    #[test]
+    fn test_six_mens_morris() {
+        let mut game = Game::new(Rules::six_mens_morris());
+        assert_eq!(game.to_notation(), "3/3/4/3/3 w p 6 6");
+        assert_eq!(game.make_move(&"d7".parse().unwrap()), Err(GameError::Placing(PlacingError::InvalidPosition)));
+
+        play(&mut game, &["a5", "a1", "c5", "c1", "e5xa1"]);
+        assert_eq!(game.pieces(Color::Black).placed, 1);
+        assert_eq!(game.to_notation(), "WWW/3/4/3/1B1 b p 3 4");
+    }
+
+    #[test]
+    fn test_lasker_morris() {
+        let mut game = Game::new(Rules::lasker_morris());
+        assert_eq!(game.pieces(Color::White).unplaced, 10);
+        assert_eq!(game.legal_moves().len(), 24);
+
+        // both placing and moving are possible while there are unplaced pieces
+        play(&mut game, &["a7", "a1"]);
+        let moves = game.legal_moves();
+        assert_eq!(moves.len(), 22 + 2);
+        assert!(moves.contains(&"a7-d7".parse().unwrap()));
+        play(&mut game, &["a7-d7", "a1-a4", "g7"]);
+        assert_eq!(game.phase(), Phase::Placing);
+        assert_eq!(game.pieces(Color::White).unplaced, 8);
+        assert_eq!(game.pieces(Color::Black).unplaced, 9);
+
+        // pieces only fly when there are 3 left, counting the unplaced ones
+        assert!(!game.flying());
+        assert_eq!(game.parse_move("a4d1"), Ok("a4-d1".parse().unwrap()));
+
+        // a player who has placed all of their pieces has to move, while the other one can still place
+        let mut game = Game::new(Rules { pieces: 4, ..Rules::lasker_morris() });
+        play(&mut game, &["a7", "a1", "b6", "a1-a4", "c5", "a4-a1", "e5", "g1"]);
+        assert_eq!(game.pieces(Color::White).unplaced, 0);
+        assert!(game.legal_moves().iter().all(|m| m.from().is_some()));
+        assert_eq!(game.make_move(&"d1".parse().unwrap()), Err(GameError::NothingToPlace));
+        assert_eq!(game.apply(Action::Place("d1".to_string())), Err(GameError::NothingToPlace));
+        play(&mut game, &["e5-e4"]);
+        assert!(game.legal_moves().iter().any(|m| m.from().is_none()));
+    }
+    #[test]
+    fn test_lasker_morris_blocked() {
+        // black has placed all of their pieces and cannot move them, while white is still placing
+        let game = Game::from_notation("BWB/3/3/BW3W/3/3/BW1 b p 4 0", Rules::lasker_morris()).unwrap();
+        assert_eq!(game.outcome(), Some(GameResult::Win { winner: Color::White, reason: WinReason::Blocked }));
+
+        // with a piece left to place, black isn't blocked
+        let game = Game::from_notation("BWB/3/3/BW3W/3/3/BW1 b p 4 1", Rules::lasker_morris()).unwrap();
+        assert_eq!(game.outcome(), None);
+
+        // unplaced pieces count towards having enough pieces
+        let game = Game::from_notation("B2/3/3/W5/3/3/3 b p 6 2", Rules::lasker_morris()).unwrap();
+        assert_eq!(game.outcome(), None);
+        let game = Game::from_notation("B2/3/3/W5/3/3/3 b p 6 1", Rules::lasker_morris()).unwrap();
+        assert_eq!(game.outcome(), Some(GameResult::Win { winner: Color::White, reason: WinReason::TooFewPieces }));
+    }
+
+    #[test]
+    fn test_morabaraba() {
+        let rules = Rules::morabaraba();
+        assert_eq!(rules.pieces, 12);
+        assert_eq!(rules.layout.mills().len(), 20);
+
+        let notation = "WWW/3/3/6/B1B/B2/W1B w m 0 0";
+        let mut game = Game::from_notation(notation, rules).unwrap();
+        game.set_draw_rules(DrawRules::none());
+
+        // moving a piece out of a mill and straight back doesn't capture
+        play(&mut game, &["d7-d6", "g1-g4"]);
+        assert!(game.legal_moves().iter().all(|m| m.capture.is_none()));
+        assert!(game.legal_moves().contains(&"d6-d7".parse().unwrap()));
+        play(&mut game, &["d6-d7", "g4-g1"]);
+
+        // neither does doing it again
+        play(&mut game, &["d7-d6", "g1-g4", "d6-d7", "g4-g1"]);
+
+        // but it does after waiting a turn
+        play(&mut game, &["d7-d6", "g1-g4", "a1-d1", "g4-g1"]);
+        assert!(!game.legal_moves().contains(&"d6-d7".parse().unwrap()));
+        assert!(game.legal_moves().contains(&"d6-d7xb2".parse().unwrap()));
+        play(&mut game, &["d6-d7xb2"]);
+
+        // without the rule, the mill captures right away
+        let mut game = Game::from_notation(notation, Rules::twelve_mens_morris()).unwrap();
+        play(&mut game, &["d7-d6", "g1-g4"]);
+        assert_eq!(game.make_move(&"d6-d7".parse().unwrap()), Err(GameError::RemovalExpected));
+        assert_eq!(game.apply(Action::Move("d6".to_string(), "d7".to_string())), Ok(()));
+        assert!(game.removal_pending());
+    }
+
+    #[test]
+    fn test_hash() {
+        let mut game = Game::default();
+        let start = game.hash();
+        assert_eq!(start, zobrist::unplaced(Color::White, 9) ^ zobrist::unplaced(Color::Black, 9));
+
+        // the same position reached in a different order has the same hash as when it is set up directly
+        play(&mut game, &["a7", "a1", "d7", "g1"]);
+        let mut other = Game::default();
+        play(&mut other, &["d7", "g1", "a7", "a1"]);
+        assert_eq!(game.hash(), other.hash());
+        assert_eq!(game.hash(), Game::from_notation("WW1/3/3/6/3/3/B1B w p 7 7", Rules::default()).unwrap().hash());
+
+        // the side to move, the unplaced pieces and the phase all change the hash
+        assert_ne!(game.hash(), Game::from_notation("WW1/3/3/6/3/3/B1B b p 6 7", Rules::default()).unwrap().hash());
+        assert_ne!(game.hash(), Game::from_notation("WW1/3/3/6/3/3/B1B w p 6 6", Rules::default()).unwrap().hash());
+        assert_ne!(Game::from_notation("WW1/3/3/6/3/3/B1B w m 0 0", Rules::default()).unwrap().board().hash(), 0);
+
+        // moves and captures update the hash, and undoing them restores it
+        play(&mut game, &["g7xg1", "d1"]);
+        let mut moved = Game::from_notation("WWW/3/3/6/3/3/BB1 w p 6 6", Rules::default()).unwrap();
+        assert_eq!(game.hash(), moved.hash());
+        play(&mut moved, &["b6", "b2"]);
+        moved.undo().unwrap();
+        moved.undo().unwrap();
+        assert_eq!(game.hash(), moved.hash());
+        while game.undo().is_ok() {}
+        assert_eq!(game.hash(), start);
+
+        // the keys are fixed, so the hash of a position is the same in every run
+        assert_eq!(start, 16017841542994234598);
+    }
+
+    #[test]
+    fn test_canonical_hash() {
+        let mut game = Game::default();
+        play(&mut game, &["a7", "d5", "g4"]);
+        let mut mirrored = Game::default();
+        play(&mut mirrored, &["g7", "d5", "a4"]);
+        let mut swapped = Game::default();
+        play(&mut swapped, &["c5", "d7", "e4"]);
+
+        assert_ne!(game.hash(), mirrored.hash());
+        assert_eq!(game.canonical_hash(), mirrored.canonical_hash());
+        assert_eq!(game.canonical_hash(), swapped.canonical_hash());
+
+        // the rest of the position still counts
+        play(&mut mirrored, &["a1"]);
+        mirrored.undo().unwrap();
+        assert_eq!(game.canonical_hash(), mirrored.canonical_hash());
+        play(&mut swapped, &["g1"]);
+        assert_ne!(game.canonical_hash(), swapped.canonical_hash());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,700 @@
+use crate::node::Node;
+use crate::point::Point;
+use crate::enums::Direction;
+use crate::errors::LayoutError;
+use crate::symmetry::{self, Transform};
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+// The fixed description of a board - its positions, which of them are adjacent, and which lines of three positions form mills.
+// Positions are numbered in alphabetical order, so that each of them corresponds to a bit in the occupancy masks of Board.
+#[derive(Debug)]
+pub struct Layout {
+    names: Vec<&'static str>,
+    points: Vec<Point>,
+    nodes: Vec<Node>,
+
+    ranks: usize,           // one more than the highest rank on the board
+    grid: Vec<Option<u8>>,  // the index of the position at each point (file * ranks + rank), for fast lookup by name
+
+    adjacent: Vec<u32>,  // for each position, a mask of the positions adjacent to it
+    mills: Vec<u32>,     // masks of all lines of three positions which form a mill
+    mills_at: Vec<Vec<u32>>, // for each position, the masks of the mills it is part of
+
+    symmetries: Vec<(Transform, Vec<u8>)>, // the transforms which map the board onto itself, with the index each position is mapped to
+
+    diagram: Option<String> // the picture of the board used by Display for Board, with a '#' for each position (see with_diagram())
+}
+
+impl Layout {
+    // Builds a layout from a list of positions and their neighbours.
+    // A mill is formed by any three positions following each other in the same direction (including diagonals).
+    pub fn new(table: &[(&'static str, Node)]) -> Self {
+        let mut table = table.to_vec();
+        table.sort_unstable_by_key(|(name, _)| *name);
+        let node = |name: &str| table.iter().find(|(n, _)| *n == name).map(|(_, node)| node).expect("ERROR: unknown neighbour in layout");
+
+        let mut mills = Vec::new();
+        for (name, first) in table.iter() {
+            for direction in [Direction::Right, Direction::Below, Direction::BelowLeft, Direction::BelowRight].iter() {
+                if let Some(second) = first.get_neighbour(*direction) {
+                    if let Some(third) = node(second).get_neighbour(*direction) {
+                        mills.push([*name, second, third]);
+                    }
+                }
+            }
+        }
+
+        Self::with_mills(&table, &mills)
+    }
+
+    // Builds a layout from a list of positions and their neighbours, with the given lines of three positions forming mills.
+    pub fn with_mills(table: &[(&'static str, Node)], mills: &[[&str; 3]]) -> Self {
+        let mut table = table.to_vec();
+        table.sort_unstable_by_key(|(name, _)| *name);
+        assert!(table.len() <= 32, "ERROR: a board can have at most 32 positions");
+
+        let names: Vec<&'static str> = table.iter().map(|(name, _)| *name).collect();
+        let points: Vec<Point> = names.iter().map(|name| name.parse().expect("ERROR: invalid position in layout")).collect();
+        let nodes: Vec<Node> = table.iter().map(|(_, node)| *node).collect();
+        let index = |name: &str| names.iter().position(|n| *n == name).expect("ERROR: unknown position in layout");
+
+        let adjacent = nodes.iter().map(|node| node.adjacent().iter().fold(0, |mask, neighbour| mask | 1 << index(neighbour))).collect();
+        let mills: Vec<u32> = mills.iter().map(|mill| mill.iter().fold(0, |mask, position| mask | 1 << index(position))).collect();
+        let mills_at = (0..nodes.len()).map(|i| mills.iter().copied().filter(|mill| mill & 1 << i != 0).collect()).collect();
+
+        let ranks = points.iter().map(|point| point.rank as usize + 1).max().unwrap_or(0);
+        let files = points.iter().map(|point| point.file as usize + 1).max().unwrap_or(0);
+        let mut grid = vec![None; files * ranks];
+        for (i, point) in points.iter().enumerate() {
+            grid[point.file as usize * ranks + point.rank as usize] = Some(i as u8);
+        }
+
+        let mut layout = Self { names, points, nodes, ranks, grid, adjacent, mills, mills_at, symmetries: Vec::new(), diagram: None };
+        layout.symmetries = symmetry::symmetries(&layout);
+        layout
+    }
+
+    // Returns the same layout, printed using the given diagram - a picture of the board in which every position is marked by a '#'.
+    // The marks are filled in the order the positions are read (row by row from the top, each row from left to right).
+    pub fn with_diagram(self, diagram: &str) -> Self {
+        assert_eq!(diagram.matches('#').count(), self.len(), "ERROR: the diagram must have a '#' for every position");
+        Self { diagram: Some(diagram.to_string()), ..self }
+    }
+
+    // the layout of the standard nine men's morris board, built once and shared by all boards
+    pub fn nine_mens_morris() -> Arc<Self> {
+        static LAYOUT: OnceLock<Arc<Layout>> = OnceLock::new();
+
+        LAYOUT.get_or_init(|| Arc::new(Self::new(&Self::nine_mens_morris_table()).with_diagram(NINE_MENS_MORRIS)))
+            .clone()
+    }
+
+    // the layout of twelve men's morris - the nine men's morris board with diagonal lines connecting the corners of the three squares
+    pub fn twelve_mens_morris() -> Arc<Self> {
+        static LAYOUT: OnceLock<Arc<Layout>> = OnceLock::new();
+
+        LAYOUT.get_or_init(|| {
+            let diagonals = [
+                // neighbours:   above left  above right  below left  below right
+                ("a7", (None,       None,       None,       Some("b6"))),
+                ("b6", (Some("a7"), None,       None,       Some("c5"))),
+                ("c5", (Some("b6"), None,       None,       None      )),
+                ("g7", (None,       None,       Some("f6"), None      )),
+                ("f6", (None,       Some("g7"), Some("e5"), None      )),
+                ("e5", (None,       Some("f6"), None,       None      )),
+                ("c3", (None,       None,       Some("b2"), None      )),
+                ("b2", (None,       Some("c3"), Some("a1"), None      )),
+                ("a1", (None,       Some("b2"), None,       None      )),
+                ("e3", (None,       None,       None,       Some("f2"))),
+                ("f2", (Some("e3"), None,       None,       Some("g1"))),
+                ("g1", (Some("f2"), None,       None,       None      )),
+            ];
+
+            let mut table = Self::nine_mens_morris_table();
+            for (name, node) in table.iter_mut() {
+                if let Some((_, (above_left, above_right, below_left, below_right))) = diagonals.iter().find(|(n, _)| n == name) {
+                    *node = node.with_diagonals(*above_left, *above_right, *below_left, *below_right);
+                }
+            }
+            Arc::new(Self::new(&table).with_diagram(TWELVE_MENS_MORRIS))
+        }).clone()
+    }
+
+    // the layout of three men's morris - a 3x3 grid, with lines along the rows, the columns and both diagonals
+    pub fn three_mens_morris() -> Arc<Self> {
+        static LAYOUT: OnceLock<Arc<Layout>> = OnceLock::new();
+
+        LAYOUT.get_or_init(|| Arc::new(Self::new(&[
+            // neighbours:   above       left        right       below                  above left  above right  below left  below right
+            ("a3", Node::new(None,       None,       Some("b3"), Some("a2")).with_diagonals(None,       None,       None,       Some("b2"))),
+            ("b3", Node::new(None,       Some("a3"), Some("c3"), Some("b2"))),
+            ("c3", Node::new(None,       Some("b3"), None,       Some("c2")).with_diagonals(None,       None,       Some("b2"), None      )),
+
+            ("a2", Node::new(Some("a3"), None,       Some("b2"), Some("a1"))),
+            ("b2", Node::new(Some("b3"), Some("a2"), Some("c2"), Some("b1")).with_diagonals(Some("a3"), Some("c3"), Some("a1"), Some("c1"))),
+            ("c2", Node::new(Some("c3"), Some("b2"), None,       Some("c1"))),
+
+            ("a1", Node::new(Some("a2"), None,       Some("b1"), None      ).with_diagonals(None,       Some("b2"), None,       None      )),
+            ("b1", Node::new(Some("b2"), Some("a1"), Some("c1"), None      )),
+            ("c1", Node::new(Some("c2"), Some("b1"), None,       None      ).with_diagonals(Some("b2"), None,       None,       None      )),
+        ]).with_diagram(THREE_MENS_MORRIS))).clone()
+    }
+
+    // the layout of six men's morris - two squares, connected at the middles of their sides. Only the sides of the squares form mills
+    pub fn six_mens_morris() -> Arc<Self> {
+        static LAYOUT: OnceLock<Arc<Layout>> = OnceLock::new();
+
+        LAYOUT.get_or_init(|| Arc::new(Self::new(&[
+            // neighbours:   above       left        right       below
+            ("a5", Node::new(None,       None,       Some("c5"), Some("a3"))),
+            ("c5", Node::new(None,       Some("a5"), Some("e5"), Some("c4"))),
+            ("e5", Node::new(None,       Some("c5"), None,       Some("e3"))),
+
+            ("b4", Node::new(None,       None,       Some("c4"), Some("b3"))),
+            ("c4", Node::new(Some("c5"), Some("b4"), Some("d4"), None      )),
+            ("d4", Node::new(None,       Some("c4"), None,       Some("d3"))),
+
+            ("a3", Node::new(Some("a5"), None,       Some("b3"), Some("a1"))),
+            ("b3", Node::new(Some("b4"), Some("a3"), None,       Some("b2"))),
+            ("d3", Node::new(Some("d4"), None,       Some("e3"), Some("d2"))),
+            ("e3", Node::new(Some("e5"), Some("d3"), None,       Some("e1"))),
+
+            ("b2", Node::new(Some("b3"), None,       Some("c2"), None      )),
+            ("c2", Node::new(None,       Some("b2"), Some("d2"), Some("c1"))),
+            ("d2", Node::new(Some("d3"), Some("c2"), None,       None      )),
+
+            ("a1", Node::new(Some("a3"), None,       Some("c1"), None      )),
+            ("c1", Node::new(Some("c2"), Some("a1"), Some("e1"), None      )),
+            ("e1", Node::new(Some("e3"), Some("c1"), None,       None      )),
+        ]).with_diagram(SIX_MENS_MORRIS))).clone()
+    }
+
+    fn nine_mens_morris_table() -> Vec<(&'static str, Node)> {
+        vec![
+            // neighbours:   above       left        right       below
+            ("a7", Node::new(None,       None,       Some("d7"), Some("a4"))),
+            ("d7", Node::new(None,       Some("a7"), Some("g7"), Some("d6"))),
+            ("g7", Node::new(None,       Some("d7"), None,       Some("g4"))),
+
+            ("b6", Node::new(None,       None,       Some("d6"), Some("b4"))),
+            ("d6", Node::new(Some("d7"), Some("b6"), Some("f6"), Some("d5"))),
+            ("f6", Node::new(None,       Some("d6"), None,       Some("f4"))),
+
+            ("c5", Node::new(None,       None,       Some("d5"), Some("c4"))),
+            ("d5", Node::new(Some("d6"), Some("c5"), Some("e5"), None      )),
+            ("e5", Node::new(None,       Some("d5"), None,       Some("e4"))),
+
+            ("a4", Node::new(Some("a7"), None,       Some("b4"), Some("a1"))),
+            ("b4", Node::new(Some("b6"), Some("a4"), Some("c4"), Some("b2"))),
+            ("c4", Node::new(Some("c5"), Some("b4"), None,       Some("c3"))),
+            ("e4", Node::new(Some("e5"), None,       Some("f4"), Some("e3"))),
+            ("f4", Node::new(Some("f6"), Some("e4"), Some("g4"), Some("f2"))),
+            ("g4", Node::new(Some("g7"), Some("f4"), None,       Some("g1"))),
+
+            ("c3", Node::new(Some("c4"), None,       Some("d3"), None      )),
+            ("d3", Node::new(None,       Some("c3"), Some("e3"), Some("d2"))),
+            ("e3", Node::new(Some("e4"), Some("d3"), None,       None      )),
+
+            ("b2", Node::new(Some("b4"), None,       Some("d2"), None      )),
+            ("d2", Node::new(Some("d3"), Some("b2"), Some("f2"), Some("d1"))),
+            ("f2", Node::new(Some("f4"), Some("d2"), None,       None      )),
+
+            ("a1", Node::new(Some("a4"), None,       Some("d1"), None      )),
+            ("d1", Node::new(Some("d2"), Some("a1"), Some("g1"), None      )),
+            ("g1", Node::new(Some("g4"), Some("d1"), None,       None      )),
+        ]
+    }
+}
+
+const THREE_MENS_MORRIS: &str = " 3 #---#---#
+   | \\ | / |
+ 2 #---#---#
+   | / | \\ |
+ 1 #---#---#
+   a   b   c
+";
+
+const SIX_MENS_MORRIS: &str = " 5 #-------#-------#
+   |       |       |
+ 4 |   #---#---#   |
+   |   |       |   |
+ 3 #---#       #---#
+   |   |       |   |
+ 2 |   #---#---#   |
+   |       |       |
+ 1 #-------#-------#
+   a   b   c   d   e
+";
+
+const NINE_MENS_MORRIS: &str = " 7 #-----------#-----------#
+   |           |           |
+ 6 |   #-------#-------#   |
+   |   |       |       |   |
+ 5 |   |   #---#---#   |   |
+   |   |   |       |   |   |
+ 4 #---#---#       #---#---#
+   |   |   |       |   |   |
+ 3 |   |   #---#---#   |   |
+   |   |       |       |   |
+ 2 |   #-------#-------#   |
+   |           |           |
+ 1 #-----------#-----------#
+   a   b   c   d   e   f   g
+";
+
+const TWELVE_MENS_MORRIS: &str = " 7 #-----------#-----------#
+   | \\         |         / |
+ 6 |   #-------#-------#   |
+   |   | \\     |     / |   |
+ 5 |   |   #---#---#   |   |
+   |   |   |       |   |   |
+ 4 #---#---#       #---#---#
+   |   |   |       |   |   |
+ 3 |   |   #---#---#   |   |
+   |   | /     |     \\ |   |
+ 2 |   #-------#-------#   |
+   | /         |         \\ |
+ 1 #-----------#-----------#
+   a   b   c   d   e   f   g
+";
+
+impl Layout { // reading from a description
+    // Builds a layout from a text description of a board, so that custom boards can be played without changing the code, e.g.:
+    //
+    //   [points]
+    //   a3 = b3 a2 b2     # each point, followed by all of its neighbours
+    //   ...
+    //   [mills]
+    //   a3 b3 c3          # the three points of each mill
+    //   ...
+    //   [diagram]
+    //    3 #---#---#      # optional - everything after the header is the diagram, as in with_diagram()
+    //   ...
+    //
+    // Everything after a '#' (outside the diagram) is a comment. Every edge has to be listed by both of its points, no two neighbours of
+    // a point can lie in the same direction from it, and the points of each mill have to lie on a straight line.
+    pub fn from_description(description: &str) -> Result<Self, LayoutError> {
+        #[derive(PartialEq, Eq)]
+        enum Section { None, Points, Mills, Diagram }
+
+        let mut section = Section::None;
+        let mut points: Vec<(usize, Point, Vec<Point>)> = Vec::new(); // the line, the point and its neighbours
+        let mut mills: Vec<(usize, Vec<Point>)> = Vec::new();         // the line and the points of the mill
+        let mut diagram = String::new();
+
+        let parse = |name: &str, line: usize| name.parse::<Point>().map_err(|_| LayoutError::InvalidPoint(line));
+
+        for (line, text) in description.lines().enumerate().map(|(i, text)| (i + 1, text)) {
+            if section == Section::Diagram {
+                diagram.push_str(text);
+                diagram.push('\n');
+                continue;
+            }
+
+            let text = text.split('#').next().unwrap_or("").trim();
+            match (text, &section) {
+                ("", _) => {}
+                ("[points]", _) => section = Section::Points,
+                ("[mills]", _) => section = Section::Mills,
+                ("[diagram]", _) => section = Section::Diagram,
+                (_, Section::Points) => {
+                    let (point, neighbours) = match text.split_once('=') {
+                        Some(parts) => parts,
+                        None => return Err(LayoutError::InvalidLine(line))
+                    };
+                    let point = parse(point.trim(), line)?;
+                    let neighbours = neighbours.split_whitespace().map(|name| parse(name, line)).collect::<Result<_, _>>()?;
+                    if points.iter().any(|(_, p, _)| *p == point) {
+                        return Err(LayoutError::DuplicatePoint(line));
+                    }
+                    points.push((line, point, neighbours));
+                }
+                (_, Section::Mills) => {
+                    let mill = text.split_whitespace().map(|name| parse(name, line)).collect::<Result<Vec<_>, _>>()?;
+                    mills.push((line, mill));
+                }
+                _ => return Err(LayoutError::InvalidLine(line))
+            }
+        }
+
+        if points.len() > 32 {
+            return Err(LayoutError::TooManyPoints);
+        }
+
+        let known = |point: &Point| points.iter().any(|(_, p, _)| p == point);
+        for (line, point, neighbours) in points.iter() {
+            let mut directions = Vec::new();
+            for neighbour in neighbours {
+                if !known(neighbour) {
+                    return Err(LayoutError::UnknownPoint(*line));
+                }
+                match direction(point, neighbour) {
+                    Some(direction) if !directions.contains(&direction) => directions.push(direction),
+                    _ => return Err(LayoutError::InvalidEdge(*line))
+                }
+                if !points.iter().any(|(_, p, others)| p == neighbour && others.contains(point)) {
+                    return Err(LayoutError::AsymmetricEdge(*line));
+                }
+            }
+        }
+
+        for (line, mill) in mills.iter() {
+            if mill.len() != 3 || mill[0] == mill[1] || mill[0] == mill[2] || mill[1] == mill[2] {
+                return Err(LayoutError::InvalidMill(*line));
+            }
+            if !mill.iter().all(known) {
+                return Err(LayoutError::UnknownPoint(*line));
+            }
+
+            // the cross product of the vectors from the first point to the other two is zero only if all three are on a line
+            let offset = |point: &Point| (point.file as i32 - mill[0].file as i32, point.rank as i32 - mill[0].rank as i32);
+            let ((x1, y1), (x2, y2)) = (offset(&mill[1]), offset(&mill[2]));
+            if x1 * y2 != x2 * y1 {
+                return Err(LayoutError::NonCollinearMill(*line));
+            }
+        }
+
+        let name = |point: &Point| intern(*point);
+
+        let table: Vec<(&'static str, Node)> = points.iter().map(|(_, point, neighbours)| {
+            let mut node = Node::new(None, None, None, None);
+            for neighbour in neighbours {
+                if let Some(direction) = direction(point, neighbour) {
+                    *node.neighbour_mut(direction) = Some(name(neighbour));
+                }
+            }
+            (name(point), node)
+        }).collect();
+        let lines: Vec<[&str; 3]> = mills.iter().map(|(_, mill)| [name(&mill[0]), name(&mill[1]), name(&mill[2])]).collect();
+
+        let layout = Self::with_mills(&table, &lines);
+        if diagram.trim().is_empty() {
+            Ok(layout)
+        } else if diagram.matches('#').count() == layout.len() {
+            Ok(layout.with_diagram(&(diagram.trim_end().to_string() + "\n")))
+        } else {
+            Err(LayoutError::InvalidDiagram)
+        }
+    }
+}
+
+// Returns the name of the point, shared by all the layouts which have it (as the layouts refer to their positions by &'static str).
+// Each name is allocated once for the whole program, so loading layouts again and again doesn't take up more memory.
+fn intern(point: Point) -> &'static str {
+    static NAMES: OnceLock<Mutex<HashMap<Point, &'static str>>> = OnceLock::new();
+
+    let mut names = NAMES.get_or_init(Default::default).lock().unwrap_or_else(|error| error.into_inner());
+    names.entry(point).or_insert_with(|| Box::leak(point.to_string().into_boxed_str()))
+}
+
+// Returns the direction from one point to another - None if they are the same point.
+fn direction(from: &Point, to: &Point) -> Option<Direction> {
+    use std::cmp::Ordering::*;
+
+    match (to.file.cmp(&from.file), to.rank.cmp(&from.rank)) {
+        (Equal, Greater) => Some(Direction::Above),
+        (Less, Equal) => Some(Direction::Left),
+        (Greater, Equal) => Some(Direction::Right),
+        (Equal, Less) => Some(Direction::Below),
+
+        (Less, Greater) => Some(Direction::AboveLeft),
+        (Greater, Greater) => Some(Direction::AboveRight),
+        (Less, Less) => Some(Direction::BelowLeft),
+        (Greater, Less) => Some(Direction::BelowRight),
+
+        (Equal, Equal) => None
+    }
+}
+
+impl Layout { // accessors
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+    // a mask with a bit set for every position on the board
+    pub fn all(&self) -> u32 {
+        if self.len() == 32 { u32::MAX } else { (1 << self.len()) - 1 }
+    }
+
+    // Returns the index of the position with the given name - None if it isn't on the board.
+    pub fn index(&self, position: &str) -> Option<usize> {
+        let bytes = position.as_bytes();
+        if bytes.len() < 2 || bytes.len() > 3 || bytes[1] == b'0' {
+            return None;
+        }
+
+        let file = bytes[0].wrapping_sub(b'a') as usize;
+        let mut rank = 0;
+        for digit in &bytes[1..] {
+            if !digit.is_ascii_digit() {
+                return None;
+            }
+            rank = rank * 10 + (digit - b'0') as usize;
+        }
+        if rank >= self.ranks {
+            return None;
+        }
+
+        self.grid.get(file * self.ranks + rank).copied().flatten().map(|index| index as usize)
+    }
+    pub fn name(&self, index: usize) -> &'static str {
+        self.names[index]
+    }
+    pub fn point(&self, index: usize) -> Point {
+        self.points[index]
+    }
+    pub fn names(&self) -> &[&'static str] {
+        &self.names
+    }
+    pub fn node(&self, index: usize) -> &Node {
+        &self.nodes[index]
+    }
+
+    pub fn adjacent(&self, index: usize) -> u32 {
+        self.adjacent[index]
+    }
+    pub fn mills(&self) -> &[u32] {
+        &self.mills
+    }
+    pub fn mills_at(&self, index: usize) -> &[u32] {
+        &self.mills_at[index]
+    }
+    pub fn diagram(&self) -> Option<&str> {
+        self.diagram.as_deref()
+    }
+
+    // the transforms which map the board onto itself (always including the identity)
+    pub fn symmetries(&self) -> impl Iterator<Item = Transform> + '_ {
+        self.symmetries.iter().map(|(transform, _)| *transform)
+    }
+    // Returns the index the given position is mapped to by the transform - None if the transform isn't a symmetry of the board.
+    pub fn transform_index(&self, transform: Transform, index: usize) -> Option<usize> {
+        self.permutation(transform).map(|permutation| permutation[index] as usize)
+    }
+    // Returns the mask of the positions in the given mask mapped by the transform - None if the transform isn't a symmetry of the board.
+    pub fn transform_mask(&self, transform: Transform, mask: u32) -> Option<u32> {
+        let permutation = self.permutation(transform)?;
+        Some((0..self.len()).filter(|i| mask & 1 << i != 0).fold(0, |result, i| result | 1 << permutation[i]))
+    }
+    fn permutation(&self, transform: Transform) -> Option<&[u8]> {
+        self.symmetries.iter().find(|(t, _)| *t == transform).map(|(_, permutation)| &permutation[..])
+    }
+
+    // Returns the indices of the positions grouped by rank, from the highest rank to the lowest, each ordered by file
+    // (i.e. the rows of the board as it's printed).
+    pub fn rows(&self) -> Vec<Vec<usize>> {
+        let mut rows: Vec<Vec<usize>> = Vec::new();
+        let mut order: Vec<usize> = (0..self.len()).collect();
+        order.sort_unstable_by_key(|&i| (std::cmp::Reverse(self.points[i].rank), self.points[i].file));
+
+        for i in order {
+            match rows.last_mut() {
+                Some(row) if self.points[row[0]].rank == self.points[i].rank => row.push(i),
+                _ => rows.push(vec![i])
+            }
+        }
+        rows
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mask(layout: &Layout, positions: &[&str]) -> u32 {
+        positions.iter().fold(0, |mask, position| mask | 1 << layout.index(position).unwrap())
+    }
+
+    #[test]
+    fn test_positions() {
+        let layout = Layout::nine_mens_morris();
+        assert_eq!(layout.len(), 24);
+        assert_eq!(layout.all().count_ones(), 24);
+        assert_eq!(layout.names()[..4], ["a1", "a4", "a7", "b2"]);
+        assert_eq!(layout.index("a4"), Some(1));
+        assert_eq!(layout.index("a8"), None);
+        assert_eq!(layout.index("h1"), None);
+        assert_eq!(layout.index("a04"), None);
+        assert_eq!(layout.index("a"), None);
+        assert_eq!(layout.index("а7"), None);
+        assert_eq!(layout.name(1), "a4");
+        assert_eq!(layout.point(1), Point::new(0, 4));
+    }
+
+    #[test]
+    fn test_adjacent() {
+        let layout = Layout::nine_mens_morris();
+        assert_eq!(layout.adjacent(layout.index("a7").unwrap()), mask(&layout, &["d7", "a4"]));
+        assert_eq!(layout.adjacent(layout.index("b4").unwrap()), mask(&layout, &["b6", "a4", "c4", "b2"]));
+        assert_eq!(layout.adjacent(layout.index("d5").unwrap()), mask(&layout, &["d6", "c5", "e5"]));
+
+        // adjacency is symmetric
+        for i in 0..layout.len() {
+            for j in 0..layout.len() {
+                assert_eq!(layout.adjacent(i) & 1 << j != 0, layout.adjacent(j) & 1 << i != 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mills() {
+        let layout = Layout::nine_mens_morris();
+        assert_eq!(layout.mills().len(), 16);
+        assert!(layout.mills().iter().all(|mill| mill.count_ones() == 3));
+
+        assert!(layout.mills().contains(&mask(&layout, &["a7", "a4", "a1"])));
+        assert!(layout.mills().contains(&mask(&layout, &["a4", "b4", "c4"])));
+        assert!(layout.mills().contains(&mask(&layout, &["d3", "d2", "d1"])));
+        assert!(!layout.mills().contains(&mask(&layout, &["c4", "e4", "f4"])));
+        assert!(!layout.mills().contains(&mask(&layout, &["d6", "d5", "d3"])));
+
+        assert_eq!(layout.mills_at(layout.index("a7").unwrap()).len(), 2);
+        assert_eq!(layout.mills_at(layout.index("d6").unwrap()), &[mask(&layout, &["b6", "d6", "f6"]), mask(&layout, &["d7", "d6", "d5"])][..]);
+    }
+
+    #[test]
+    fn test_rows() {
+        let layout = Layout::nine_mens_morris();
+        let rows = layout.rows();
+        assert_eq!(rows.iter().map(Vec::len).collect::<Vec<_>>(), [3, 3, 3, 6, 3, 3, 3]);
+        assert_eq!(rows[0].iter().map(|&i| layout.name(i)).collect::<Vec<_>>(), ["a7", "d7", "g7"]);
+        assert_eq!(rows[3].iter().map(|&i| layout.name(i)).collect::<Vec<_>>(), ["a4", "b4", "c4", "e4", "f4", "g4"]);
+    }
+
+    #[test]
+    fn test_twelve_mens_morris() {
+        let layout = Layout::twelve_mens_morris();
+        assert_eq!(layout.len(), 24);
+        assert_eq!(layout.mills().len(), 20);
+        assert_eq!(layout.adjacent(layout.index("b6").unwrap()), mask(&layout, &["d6", "b4", "a7", "c5"]));
+        assert_eq!(layout.adjacent(layout.index("d6").unwrap()), Layout::nine_mens_morris().adjacent(layout.index("d6").unwrap()));
+
+        assert!(layout.mills().contains(&mask(&layout, &["a7", "b6", "c5"])));
+        assert!(layout.mills().contains(&mask(&layout, &["g7", "f6", "e5"])));
+        assert!(layout.mills().contains(&mask(&layout, &["a1", "b2", "c3"])));
+        assert!(layout.mills().contains(&mask(&layout, &["g1", "f2", "e3"])));
+        assert_eq!(layout.mills_at(layout.index("b6").unwrap()).len(), 3);
+
+        for i in 0..layout.len() {
+            for j in 0..layout.len() {
+                assert_eq!(layout.adjacent(i) & 1 << j != 0, layout.adjacent(j) & 1 << i != 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_three_mens_morris() {
+        let layout = Layout::three_mens_morris();
+        assert_eq!(layout.len(), 9);
+        assert_eq!(layout.mills().len(), 8);
+        assert!(layout.mills().contains(&mask(&layout, &["a3", "b2", "c1"])));
+        assert!(layout.mills().contains(&mask(&layout, &["c3", "b2", "a1"])));
+        assert_eq!(layout.adjacent(layout.index("b2").unwrap()).count_ones(), 8);
+        assert_eq!(layout.adjacent(layout.index("a3").unwrap()), mask(&layout, &["b3", "a2", "b2"]));
+        assert_eq!(layout.rows().iter().map(Vec::len).collect::<Vec<_>>(), [3, 3, 3]);
+    }
+
+    #[test]
+    fn test_six_mens_morris() {
+        let layout = Layout::six_mens_morris();
+        assert_eq!(layout.len(), 16);
+        assert_eq!(layout.mills().len(), 8);
+        assert!(layout.mills().contains(&mask(&layout, &["a5", "c5", "e5"])));
+        assert!(layout.mills().contains(&mask(&layout, &["b4", "b3", "b2"])));
+        assert!(!layout.mills().contains(&mask(&layout, &["c5", "c4", "c2"])));
+        assert_eq!(layout.adjacent(layout.index("c4").unwrap()), mask(&layout, &["c5", "b4", "d4"]));
+
+        for layout in [Layout::three_mens_morris(), Layout::six_mens_morris()].iter() {
+            for i in 0..layout.len() {
+                for j in 0..layout.len() {
+                    assert_eq!(layout.adjacent(i) & 1 << j != 0, layout.adjacent(j) & 1 << i != 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_description() {
+        let layout = Layout::from_description(include_str!("../boards/six_mens_morris.txt")).unwrap();
+        let six = Layout::six_mens_morris();
+        assert_eq!(layout.names(), six.names());
+
+        // loading the layout again shares the names of its positions, instead of allocating them again
+        let again = Layout::from_description(include_str!("../boards/six_mens_morris.txt")).unwrap();
+        assert!(layout.names().iter().zip(again.names()).all(|(a, b)| std::ptr::eq(*a, *b)));
+        assert_eq!(layout.diagram(), six.diagram());
+        for i in 0..layout.len() {
+            assert_eq!(layout.adjacent(i), six.adjacent(i));
+            assert_eq!(layout.node(i).get_neighbour(Direction::Right), six.node(i).get_neighbour(Direction::Right));
+        }
+        let mut mills = layout.mills().to_vec();
+        let mut expected = six.mills().to_vec();
+        mills.sort_unstable();
+        expected.sort_unstable();
+        assert_eq!(mills, expected);
+
+        // a board without a diagram, with a diagonal mill
+        let layout = Layout::from_description("[points]\na1 = b2 # corner\nb2 = a1 c3\nc3 = b2\n\n[mills]\na1 b2 c3\n").unwrap();
+        assert_eq!(layout.len(), 3);
+        assert_eq!(layout.mills(), &[0b111]);
+        assert_eq!(layout.node(1).get_neighbour(Direction::AboveRight), Some("c3"));
+        assert_eq!(layout.diagram(), None);
+    }
+
+    #[test]
+    fn test_from_description_errors() {
+        let points = "[points]\na1 = a2\na2 = a1 a3\na3 = a2\n";
+        assert!(Layout::from_description(points).is_ok());
+
+        assert_eq!(Layout::from_description("a1 = a2\n").err(), Some(LayoutError::InvalidLine(1)));
+        assert_eq!(Layout::from_description("[points]\na1 a2\n").err(), Some(LayoutError::InvalidLine(2)));
+        assert_eq!(Layout::from_description("[board]\n").err(), Some(LayoutError::InvalidLine(1)));
+        assert_eq!(Layout::from_description("[points]\na1 = 2a\n").err(), Some(LayoutError::InvalidPoint(2)));
+        assert_eq!(Layout::from_description("[points]\na1 =\na1 =\n").err(), Some(LayoutError::DuplicatePoint(3)));
+        assert_eq!(Layout::from_description("[points]\na1 = a2\n").err(), Some(LayoutError::UnknownPoint(2)));
+        assert_eq!(Layout::from_description("[points]\na1 = a2\na2 =\n").err(), Some(LayoutError::AsymmetricEdge(2)));
+        assert_eq!(Layout::from_description("[points]\na1 = a1\n").err(), Some(LayoutError::InvalidEdge(2)));
+        assert_eq!(Layout::from_description("[points]\na1 = a2 a3\na2 = a1\na3 = a1\n").err(), Some(LayoutError::InvalidEdge(2)));
+
+        let mills = |mill: &str| Layout::from_description(&format!("{}[mills]\n{}\n", points, mill)).err();
+        assert_eq!(mills("a1 a2 a3"), None);
+        assert_eq!(mills("a1 a2"), Some(LayoutError::InvalidMill(6)));
+        assert_eq!(mills("a1 a2 a1"), Some(LayoutError::InvalidMill(6)));
+        assert_eq!(mills("a1 a2 a4"), Some(LayoutError::UnknownPoint(6)));
+        assert_eq!(Layout::from_description("[points]\na1 = a2\na2 = a1\nb3 =\n[mills]\na1 a2 b3\n").err(), Some(LayoutError::NonCollinearMill(6)));
+
+        let many: String = (1..=33).map(|rank| format!("a{} =\n", rank)).collect();
+        assert_eq!(Layout::from_description(&format!("[points]\n{}", many)).err(), Some(LayoutError::TooManyPoints));
+        assert_eq!(Layout::from_description(&format!("{}[diagram]\n # # \n", points)).err(), Some(LayoutError::InvalidDiagram));
+        assert_eq!(Layout::from_description(&format!("{}[diagram]\n#\n#\n#\n\n", points)).unwrap().diagram(), Some("#\n#\n#\n"));
+    }
+
+    #[test]
+    fn test_symmetries() {
+        assert_eq!(Layout::nine_mens_morris().symmetries().count(), 16);
+        assert_eq!(Layout::twelve_mens_morris().symmetries().count(), 16);
+        assert_eq!(Layout::six_mens_morris().symmetries().count(), 16);
+        assert_eq!(Layout::three_mens_morris().symmetries().count(), 8);
+        assert!(Layout::three_mens_morris().symmetries().all(|transform| !transform.swap_squares));
+
+        let layout = Layout::nine_mens_morris();
+        let index = |name| layout.index(name).unwrap();
+        let rotate = Transform { rotation: 1, ..Transform::IDENTITY };
+        let swap = Transform { swap_squares: true, ..Transform::IDENTITY };
+        assert_eq!(layout.transform_index(rotate, index("a7")), Some(index("a1")));
+        assert_eq!(layout.transform_index(rotate, index("d7")), Some(index("a4")));
+        assert_eq!(layout.transform_index(swap, index("a7")), Some(index("c5")));
+        assert_eq!(layout.transform_index(swap, index("b4")), Some(index("b4")));
+        assert_eq!(layout.transform_mask(swap, mask(&layout, &["a7", "d7", "g7"])), Some(mask(&layout, &["c5", "d5", "e5"])));
+        assert_eq!(Layout::three_mens_morris().transform_mask(swap, 1), None);
+
+        // a board without any symmetry other than the identity
+        let layout = Layout::from_description("[points]\na1 = b1\nb1 = a1 c1\nc1 = b1\nc2 =\n").unwrap();
+        assert_eq!(layout.symmetries().collect::<Vec<_>>(), [Transform::IDENTITY]);
+    }
+}
@@ -0,0 +1,40 @@
+pub mod enums;
+pub mod errors;
+pub mod action;
+pub mod point;
+pub mod moves;
+pub mod layout;
+pub mod board;
+pub mod game;
+pub mod node;
+pub mod pieces;
+pub mod ai;
+pub mod rng;
+pub mod rules;
+pub mod player;
+pub mod zobrist;
+pub mod symmetry;
+pub mod tablebase;
+pub mod engine;
+pub mod transposition;
+pub mod mcts;
+pub mod evaluation;
+
+pub use crate::enums::{Color, Phase, State, GameResult, WinReason, DrawReason};
+pub use crate::errors::{PlacingError, MovingError, RemovingError, GameError, ParseMoveError, LoadError, NotationError, LayoutError, TablebaseError, EvaluationError};
+pub use crate::action::Action;
+pub use crate::point::Point;
+pub use crate::moves::{Move, MoveKind};
+pub use crate::layout::Layout;
+pub use crate::board::Board;
+pub use crate::game::Game;
+pub use crate::pieces::Pieces;
+pub use crate::ai::AlphaBeta;
+pub use crate::player::{Player, GameView, HumanPlayer, ScriptedPlayer, RandomPlayer};
+pub use crate::rules::{Rules, DoubleMill, DrawRules};
+pub use crate::symmetry::Transform;
+pub use crate::tablebase::{Tablebase, Value};
+pub use crate::engine::Engine;
+pub use crate::transposition::{TranspositionTable, Replacement};
+pub use crate::mcts::{Mcts, Playout};
+pub use crate::evaluation::{Evaluation, Feature, Stage};
@@ -1,13 +1,91 @@
-mod enums;
-mod errors;
-mod board;
-mod game;
-mod node;
-mod pieces;
+mod cli;
 
-use crate::game::Game;
+use solution::{Game, Rules, DoubleMill, Engine};
 
+use std::env;
+use std::io;
+use std::sync::Arc;
+
+const USAGE: &str = "[--load <file>] [--variant three|six|nine|twelve|morabaraba|lasker] [--board <file>] [--pieces <count>] [--no-flying] [--unprotected-mills] [--double-mill-captures-two] [--tablebase <file>] [--evaluation <file>] [--engine]";
+
+// Starts a new game, or continues one saved with the "save" command (--load). The other options choose the variant and change its rules,
+// for playing house rules: a custom board read from a file (see boards/ for an example), the number of pieces of each player,
+// no flying with 3 pieces, allowing pieces to be removed from mills, and removing two pieces when a move forms two mills at once.
+// An endgame tablebase written by the tablebase tool for the same rules (--tablebase) lets the computer play the endgames it covers perfectly.
+// The weights of the computer's evaluation can be tuned in a file (--evaluation, see evaluation/default.txt).
+// With --engine, the game isn't played interactively - instead, the engine protocol (see Engine) is spoken over stdin and stdout.
 fn main() {
-    let mut game = Game::new();
-	game.game_loop();
+    let args: Vec<String> = env::args().collect();
+    let usage = || -> ! {
+        println!("Usage: {} {}", args[0], USAGE);
+        std::process::exit(1);
+    };
+
+    // the variant is chosen first, so that the other options change its rules regardless of their order
+    let mut rules = match args.iter().position(|arg| arg == "--variant") {
+        Some(i) => args.get(i + 1).and_then(|name| Rules::variant(name)).unwrap_or_else(|| usage()),
+        None => Rules::default()
+    };
+
+    let mut load = None;
+    let mut tablebase = None;
+    let mut evaluation = None;
+    let mut engine = false;
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--load" => {
+                load = Some(args.get(i + 1).unwrap_or_else(|| usage()));
+                i += 1;
+            }
+            "--variant" => i += 1,
+            "--tablebase" => {
+                tablebase = Some(args.get(i + 1).unwrap_or_else(|| usage()));
+                i += 1;
+            }
+            "--board" => {
+                let path = args.get(i + 1).unwrap_or_else(|| usage());
+                rules.layout = Arc::new(cli::load_layout(path).unwrap_or_else(|| std::process::exit(1)));
+                i += 1;
+            }
+            "--pieces" => {
+                rules.pieces = match args.get(i + 1).map(|count| count.parse::<u8>()) {
+                    Some(Ok(count)) if (3..=12).contains(&count) => count,
+                    _ => usage()
+                };
+                i += 1;
+            }
+            "--evaluation" => {
+                let path = args.get(i + 1).unwrap_or_else(|| usage());
+                evaluation = Some(cli::load_evaluation(path).unwrap_or_else(|| std::process::exit(1)));
+                i += 1;
+            }
+            "--engine" => engine = true,
+            "--no-flying" => rules.flying = false,
+            "--unprotected-mills" => rules.protect_mills = false,
+            "--double-mill-captures-two" => rules.double_mill = DoubleMill::TwoCaptures,
+            _ => usage()
+        }
+        i += 1;
+    }
+
+    if !rules.fits() {
+        println!("ERROR: The board has only {} positions, which can't hold {} pieces of each player!", rules.layout.len(), rules.pieces);
+        std::process::exit(1);
+    }
+
+    // the tablebase is read once all the rules are known, as it can only be used with the rules it was generated for
+    let tablebase = tablebase.map(|path| Arc::new(cli::load_tablebase(path, &rules).unwrap_or_else(|| std::process::exit(1))));
+
+    if engine {
+        Engine::new(rules, tablebase, io::stdout()).with_evaluation(evaluation.unwrap_or_default()).run(io::stdin().lock());
+        return;
+    }
+
+    let (mut game, loaded) = match load {
+        Some(path) => (cli::load_game(path, rules).unwrap_or_else(|| std::process::exit(1)), true),
+        None => (Game::new(rules), false)
+    };
+
+	cli::game_loop(&mut game, loaded, tablebase, evaluation.unwrap_or_default());
 }
@@ -0,0 +1,261 @@
+use crate::game::Game;
+use crate::moves::Move;
+use crate::enums::{Color, GameResult};
+use crate::rng::Rng;
+
+use std::time::{Duration, Instant};
+
+// how the moves of a playout (the rest of a simulated game, after the moves in the tree) are chosen
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Playout {
+    Random,     // uniformly among the legal moves
+    Heuristic   // a move forming a mill if there is one, otherwise like Random - which makes the playouts much more like real games
+}
+
+// a position in the search tree, reached by a move from its parent
+struct Node {
+    m: Option<Move>,        // None for the root
+    player: Color,          // the player who made the move
+    children: Vec<usize>,
+    untried: Vec<Move>,     // the legal moves which don't have a child yet
+    visits: u32,
+    wins: f64               // the results of the simulations through the node, for its player - 1 for a win and 0.5 for a draw
+}
+
+// A computer player choosing moves by Monte Carlo tree search - it simulates many games from the position, building a tree of the moves
+// played in them, and chooses the move which was simulated the most. Within the tree, moves are chosen by UCT (balancing the moves
+// which won most often with the ones which were tried least), and after leaving it, by the playout policy until the game ends.
+// Each move is a whole turn (see Game.legal_moves()), so all phases of the game are handled the same way.
+// The search stops after the given number of simulations, or when its time runs out; the same seed gives the same moves (unless it's stopped by time).
+pub struct Mcts {
+    pub iterations: u32,
+    pub simulations: u32,   // the number of simulations run by the last search
+
+    time_limit: Option<Duration>,
+    playout: Playout,
+    exploration: f64,       // the weight of trying the less simulated moves in UCT
+    playout_limit: u32,     // the number of moves after which a playout is adjudicated by the pieces left
+    rng: Rng
+}
+
+impl Mcts {
+    pub fn new(iterations: u32, seed: u64) -> Self {
+        Self {
+            iterations, simulations: 0,
+            time_limit: None, playout: Playout::Heuristic, exploration: std::f64::consts::SQRT_2, playout_limit: 200, rng: Rng::new(seed)
+        }
+    }
+
+    // Returns the same search, which stops after the given time (even if it hasn't run all of its simulations).
+    pub fn with_time_limit(self, time_limit: Duration) -> Self {
+        Self { time_limit: Some(time_limit), ..self }
+    }
+
+    // Returns the same search, with the given playout policy.
+    pub fn with_playout(self, playout: Playout) -> Self {
+        Self { playout, ..self }
+    }
+
+    // Returns the same search, with the given exploration constant for UCT (the square root of 2 by default).
+    pub fn with_exploration(self, exploration: f64) -> Self {
+        Self { exploration, ..self }
+    }
+
+    // Returns the most simulated move for the current player of the game, or None if they have no legal moves.
+    // A single legal move is returned right away, without simulating anything.
+    pub fn best_move(&mut self, game: &Game) -> Option<Move> {
+        let start = Instant::now();
+        self.simulations = 0;
+
+        let mut tree = vec![Node { m: None, player: game.current().other(), children: Vec::new(), untried: Self::moves(game), visits: 0, wins: 0.0 }];
+        match tree[0].untried.len() {
+            0 => return None,
+            1 => return tree[0].untried.pop(),
+            _ => {}
+        }
+
+        let mut game = game.clone();
+        while self.simulations < self.iterations.max(1) && self.time_limit.map_or(true, |limit| start.elapsed() < limit) {
+            self.simulate(&mut tree, &mut game);
+            self.simulations += 1;
+        }
+
+        tree[0].children.iter().max_by_key(|child| tree[**child].visits).and_then(|child| tree[*child].m)
+    }
+
+    // Runs a single simulation from the root, adding a node for its first move outside the tree. The game is restored afterwards.
+    fn simulate(&mut self, tree: &mut Vec<Node>, game: &mut Game) {
+        let mut path = vec![0];
+        let mut played = 0;
+
+        // selection - down the tree, while all the moves of the nodes have children
+        let mut node = 0;
+        while tree[node].untried.is_empty() && !tree[node].children.is_empty() {
+            node = self.select(tree, node);
+            Self::play(game, tree[node].m.expect("ERROR: a child node without a move"), &mut played);
+            path.push(node);
+        }
+
+        // expansion - a node for one of the moves which haven't been tried yet
+        if !tree[node].untried.is_empty() && !game.is_over() {
+            let i = self.rng.below(tree[node].untried.len());
+            let m = tree[node].untried.swap_remove(i);
+            let player = game.current();
+            Self::play(game, m, &mut played);
+
+            let child = tree.len();
+            tree.push(Node { m: Some(m), player, children: Vec::new(), untried: Self::moves(game), visits: 0, wins: 0.0 });
+            tree[node].children.push(child);
+            path.push(child);
+        }
+
+        // simulation - to the end of the game
+        let mut plies = 0;
+        while !game.is_over() && plies < self.playout_limit {
+            let moves = game.legal_moves();
+            let m = self.playout_move(&moves);
+            Self::play(game, m, &mut played);
+            plies += 1;
+        }
+        let winner = Self::winner(game);
+
+        // backpropagation - the result, for the player of each node
+        for node in path {
+            tree[node].visits += 1;
+            tree[node].wins += match winner {
+                Some(winner) if winner == tree[node].player => 1.0,
+                Some(_) => 0.0,
+                None => 0.5
+            };
+        }
+
+        for _ in 0..played {
+            game.undo().expect("ERROR: could not take back a simulated move");
+        }
+    }
+
+    // the child with the highest upper confidence bound (UCT) - its win rate, plus a bonus for being simulated less than its siblings
+    fn select(&self, tree: &[Node], node: usize) -> usize {
+        let parent_visits = (tree[node].visits as f64).ln();
+        let uct = |child: usize| {
+            let child = &tree[child];
+            child.wins / child.visits as f64 + self.exploration * (parent_visits / child.visits as f64).sqrt()
+        };
+
+        *tree[node].children.iter().max_by(|a, b| uct(**a).total_cmp(&uct(**b))).expect("ERROR: a node without children")
+    }
+
+    fn playout_move(&mut self, moves: &[Move]) -> Move {
+        if self.playout == Playout::Heuristic {
+            let captures: Vec<&Move> = moves.iter().filter(|m| m.capture.is_some()).collect();
+            if !captures.is_empty() {
+                return *captures[self.rng.below(captures.len())];
+            }
+        }
+        moves[self.rng.below(moves.len())]
+    }
+
+    // the legal moves of a position in the tree - none once the game is over
+    fn moves(game: &Game) -> Vec<Move> {
+        if game.is_over() {
+            Vec::new()
+        } else {
+            game.legal_moves()
+        }
+    }
+
+    fn play(game: &mut Game, m: Move, played: &mut u32) {
+        game.make_move(&m).expect("ERROR: generated an illegal move");
+        *played += 1;
+    }
+
+    // The winner of a simulated game - None for a draw. A playout cut off by its limit is won by the player with more pieces (placed or not).
+    fn winner(game: &Game) -> Option<Color> {
+        match game.outcome() {
+            Some(GameResult::Win { winner, .. }) => Some(winner),
+            Some(GameResult::Draw { .. }) => None,
+            None => {
+                let pieces = |color: Color| {
+                    let pieces = game.pieces(color);
+                    pieces.placed + pieces.unplaced
+                };
+                match pieces(Color::White).cmp(&pieces(Color::Black)) {
+                    std::cmp::Ordering::Greater => Some(Color::White),
+                    std::cmp::Ordering::Less => Some(Color::Black),
+                    std::cmp::Ordering::Equal => None
+                }
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::Rules;
+
+    fn play(game: &mut Game, moves: &[&str]) {
+        for m in moves {
+            assert_eq!(game.make_move(&game.parse_move(m).unwrap()), Ok(()), "{}", m);
+        }
+    }
+
+    #[test]
+    fn test_completes_mill() {
+        let mut game = Game::default();
+        play(&mut game, &["a7", "b6", "d7", "d6"]);
+
+        // white can form a mill at g7 right away
+        let mut search = Mcts::new(2000, 1);
+        let m = search.best_move(&game).unwrap();
+        assert_eq!(m.to(), "g7".parse().unwrap());
+        assert!(m.capture.is_some());
+        assert_eq!(search.simulations, 2000);
+
+        // the game is restored after the simulations
+        assert_eq!(game.history().len(), 4);
+    }
+
+    #[test]
+    fn test_reproducible() {
+        let mut game = Game::default();
+        play(&mut game, &["a7", "b6", "g1", "d6"]);
+
+        for playout in [Playout::Random, Playout::Heuristic].iter() {
+            let m = Mcts::new(300, 7).with_playout(*playout).best_move(&game);
+            assert_eq!(Mcts::new(300, 7).with_playout(*playout).best_move(&game), m);
+            assert!(game.legal_moves().contains(&m.unwrap()));
+        }
+    }
+
+    #[test]
+    fn test_phases() {
+        // a win in one move of three men's morris, in the moving phase
+        let game = Game::from_notation("WW1/BWB/B11 w m 0 0", Rules::three_mens_morris()).unwrap();
+        let m = Mcts::new(500, 3).best_move(&game).unwrap();
+        assert_eq!(m.to(), "c3".parse().unwrap());
+
+        // black has 3 pieces left, so it flies
+        let mut game = Game::default();
+        play(&mut game, &["d1", "f4", "d2", "e5", "d3xe5", "g7", "b6", "c5", "f2", "g1", "b2xf4", "e4", "b4xc5", "g4xf2", "f2xe4", "f6", "e3", "d5",
+                          "d3-c3", "g7-d7", "e3-d3xd5", "d7-d6", "c3-c4", "g4-g7", "d3-e3", "g7-d7", "e3-d3xd7", "f6*a4", "c4-c5", "a4*d5", "b4-c4"]);
+        let m = Mcts::new(500, 3).best_move(&game).unwrap();
+        assert!(game.legal_moves().contains(&m));
+
+        let mut game = Game::default();
+        game.resign(Color::White).unwrap();
+        assert_eq!(Mcts::new(100, 3).best_move(&game), None);
+    }
+
+    #[test]
+    fn test_time_limit() {
+        let start = Instant::now();
+        let mut search = Mcts::new(u32::MAX, 5).with_time_limit(Duration::from_millis(100));
+        let m = search.best_move(&Game::default()).unwrap();
+
+        assert!(Game::default().legal_moves().contains(&m));
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert!(search.simulations > 0 && search.simulations < u32::MAX);
+    }
+}
@@ -0,0 +1,231 @@
+use crate::point::Point;
+use crate::layout::Layout;
+use crate::symmetry::Transform;
+use crate::errors::ParseMoveError;
+
+use std::str::FromStr;
+
+// the way a piece gets to its target position
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum MoveKind {
+    Place(Point),       // placing a new piece (placing phase)
+    Step(Point, Point), // moving a piece to an adjacent position (moving phase)
+    Fly(Point, Point)   // moving a piece to any position (moving phase, when the player has only 3 pieces left)
+}
+
+// a whole turn of a player - placing or moving a piece, along with the opponent's piece to remove if that forms a mill
+// (and a second one if it forms two mills at once, when the rules allow capturing twice)
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Move {
+    pub kind: MoveKind,
+    pub capture: Option<Point>,
+    pub second_capture: Option<Point>
+}
+
+impl Move {
+    pub fn place(to: Point) -> Self {
+        Self { kind: MoveKind::Place(to), capture: None, second_capture: None }
+    }
+    pub fn step(from: Point, to: Point) -> Self {
+        Self { kind: MoveKind::Step(from, to), capture: None, second_capture: None }
+    }
+    pub fn fly(from: Point, to: Point) -> Self {
+        Self { kind: MoveKind::Fly(from, to), capture: None, second_capture: None }
+    }
+
+    // Returns the same move, also removing the opponent's piece at the given position (as the second capture if it already has one).
+    pub fn with_capture(self, capture: Point) -> Self {
+        match self.capture {
+            None => Self { capture: Some(capture), ..self },
+            Some(_) => Self { second_capture: Some(capture), ..self }
+        }
+    }
+
+    // the positions of the captured pieces, in the order they are removed
+    pub fn captures(&self) -> impl Iterator<Item = Point> {
+        self.capture.into_iter().chain(self.second_capture)
+    }
+
+    // the position the piece is taken from - None when placing
+    pub fn from(&self) -> Option<Point> {
+        match self.kind {
+            MoveKind::Place(_) => None,
+            MoveKind::Step(from, _) | MoveKind::Fly(from, _) => Some(from)
+        }
+    }
+    // the position the piece ends up at
+    pub fn to(&self) -> Point {
+        match self.kind {
+            MoveKind::Place(to) | MoveKind::Step(_, to) | MoveKind::Fly(_, to) => to
+        }
+    }
+
+    // Returns the same move on a board changed by the given transform (see Board.transform()),
+    // None if the transform isn't a symmetry of the layout or the move has a position which isn't on the board.
+    pub fn transform(&self, layout: &Layout, transform: Transform) -> Option<Self> {
+        let map = |point: Point| -> Option<Point> {
+            let index = layout.index(&point.to_string())?;
+            layout.transform_index(transform, index).map(|index| layout.point(index))
+        };
+
+        let kind = match self.kind {
+            MoveKind::Place(to) => MoveKind::Place(map(to)?),
+            MoveKind::Step(from, to) => MoveKind::Step(map(from)?, map(to)?),
+            MoveKind::Fly(from, to) => MoveKind::Fly(map(from)?, map(to)?)
+        };
+        let capture = match self.capture {
+            Some(capture) => Some(map(capture)?),
+            None => None
+        };
+        let second_capture = match self.second_capture {
+            Some(capture) => Some(map(capture)?),
+            None => None
+        };
+
+        Some(Self { kind, capture, second_capture })
+    }
+}
+
+// Splits a single position (a letter followed by digits) from the start of the string.
+fn split_point(s: &str) -> Result<(Point, &str), ParseMoveError> {
+    let length = s.chars().next().map_or(0, |c| c.len_utf8()) + s.chars().skip(1).take_while(|c| c.is_ascii_digit()).count();
+    let (point, rest) = s.split_at(length);
+
+    Ok((point.parse()?, rest))
+}
+
+impl FromStr for Move {
+    type Err = ParseMoveError;
+
+    // Parses a move in the format produced by Display - "a7" for placing, "a7-a4" for a step, "a7*g1" for flying,
+    // each optionally followed by "x" and the position of the captured piece (e.g. "a7-a4xd1"), or of two pieces (e.g. "a7-a4xd1xg1").
+    // Steps can also be written without the separator, e.g. "a7a4".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (first, rest) = split_point(s.trim())?;
+
+        let (kind, rest) = match rest.chars().next() {
+            Some('-') => {
+                let (second, rest) = split_point(&rest[1..])?;
+                (MoveKind::Step(first, second), rest)
+            }
+            Some('*') => {
+                let (second, rest) = split_point(&rest[1..])?;
+                (MoveKind::Fly(first, second), rest)
+            }
+            Some(c) if c.is_ascii_lowercase() && c != 'x' => {
+                let (second, rest) = split_point(rest)?;
+                (MoveKind::Step(first, second), rest)
+            }
+            _ => (MoveKind::Place(first), rest)
+        };
+
+        let mut parsed = Self { kind, capture: None, second_capture: None };
+        let mut rest = rest;
+        while !rest.is_empty() {
+            if !rest.starts_with('x') || parsed.second_capture.is_some() {
+                return Err(ParseMoveError::InvalidFormat);
+            }
+
+            let (capture, remaining) = split_point(&rest[1..])?;
+            parsed = parsed.with_capture(capture);
+            rest = remaining;
+        }
+
+        Ok(parsed)
+    }
+}
+
+use std::fmt::{self, Display, Formatter};
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.kind {
+            MoveKind::Place(to)      => write!(f, "{}", to)?,
+            MoveKind::Step(from, to) => write!(f, "{}-{}", from, to)?,
+            MoveKind::Fly(from, to)  => write!(f, "{}*{}", from, to)?,
+        }
+        for capture in self.captures() {
+            write!(f, "x{}", capture)?;
+        }
+
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(s: &str) -> Point {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("a7".parse(), Ok(Move::place(p("a7"))));
+        assert_eq!("a7-a4".parse(), Ok(Move::step(p("a7"), p("a4"))));
+        assert_eq!("a7a4".parse(), Ok(Move::step(p("a7"), p("a4"))));
+        assert_eq!("a7*g1".parse(), Ok(Move::fly(p("a7"), p("g1"))));
+        assert_eq!("g7xd1".parse(), Ok(Move::place(p("g7")).with_capture(p("d1"))));
+        assert_eq!("a7-a4xd1".parse(), Ok(Move::step(p("a7"), p("a4")).with_capture(p("d1"))));
+        assert_eq!("a7*g1xb2\r\n".parse(), Ok(Move::fly(p("a7"), p("g1")).with_capture(p("b2"))));
+        assert_eq!("d7xa1xg1".parse(), Ok(Move::place(p("d7")).with_capture(p("a1")).with_capture(p("g1"))));
+
+        assert_eq!("".parse::<Move>(), Err(ParseMoveError::InvalidPosition));
+        assert_eq!("a7-".parse::<Move>(), Err(ParseMoveError::InvalidPosition));
+        assert_eq!("a7x".parse::<Move>(), Err(ParseMoveError::InvalidPosition));
+        assert_eq!("a7+a4".parse::<Move>(), Err(ParseMoveError::InvalidFormat));
+        assert_eq!("a7xd1d2".parse::<Move>(), Err(ParseMoveError::InvalidFormat));
+        assert_eq!("a7xd1xd2xd3".parse::<Move>(), Err(ParseMoveError::InvalidFormat));
+    }
+
+    #[test]
+    fn test_display() {
+        let moves = ["a7", "a7-a4", "a7*g1", "g7xd1", "a7-a4xd1", "a7*g1xb2", "d7xa1xg1"];
+        for text in moves.iter() {
+            assert_eq!(text.parse::<Move>().unwrap().to_string(), *text);
+        }
+    }
+
+    #[test]
+    fn test_positions() {
+        let placing = Move::place(p("a7"));
+        assert_eq!(placing.from(), None);
+        assert_eq!(placing.to(), p("a7"));
+
+        let flying = Move::fly(p("a7"), p("g1")).with_capture(p("d1"));
+        assert_eq!(flying.from(), Some(p("a7")));
+        assert_eq!(flying.to(), p("g1"));
+        assert_eq!(flying.capture, Some(p("d1")));
+        assert_eq!(flying.captures().collect::<Vec<_>>(), [p("d1")]);
+
+        let double = flying.with_capture(p("d2"));
+        assert_eq!(double.second_capture, Some(p("d2")));
+        assert_eq!(double.captures().collect::<Vec<_>>(), [p("d1"), p("d2")]);
+    }
+
+    #[test]
+    fn test_hash() {
+        use std::collections::HashSet;
+
+        let moves: HashSet<Move> = ["a7-a4", "a7a4", "a7*a4", "a7-a4xd1"].iter().map(|text| text.parse().unwrap()).collect();
+        assert_eq!(moves.len(), 3);
+    }
+
+    #[test]
+    fn test_transform() {
+        let layout = Layout::nine_mens_morris();
+        let rotate = Transform { rotation: 1, ..Transform::IDENTITY };
+        let swap = Transform { swap_squares: true, reflect: true, ..Transform::IDENTITY };
+
+        let m: Move = "d7-d6xa1".parse().unwrap();
+        assert_eq!(m.transform(&layout, rotate), Some("a4-b4xg1".parse().unwrap()));
+        assert_eq!(m.transform(&layout, swap), Some("d5-d6xe3".parse().unwrap()));
+        assert_eq!(m.transform(&layout, rotate).and_then(|m| m.transform(&layout, rotate.inverse())), Some(m));
+        assert_eq!(Move::place(p("a2")).transform(&layout, rotate), None);
+
+        let double: Move = "a7*g1xd1xd2".parse().unwrap();
+        assert_eq!(double.transform(&layout, Transform::IDENTITY), Some(double));
+        assert_eq!(double.transform(&Layout::three_mens_morris(), Transform::IDENTITY), None);
+    }
+}
@@ -1,58 +1,84 @@
-use crate::enums::Color;
-use crate::enums::State;
 use crate::enums::Direction;
 
-#[derive(Clone, Copy)]
+// a position in the layout of a board, described by its neighbours
+#[derive(Clone, Copy, Debug)]
 pub struct Node {
-    pub state: State,
-
     // neighbours
     pub above: Option<&'static str>,
     pub left:  Option<&'static str>,
     pub right: Option<&'static str>,
     pub below: Option<&'static str>,
+
+    // diagonal neighbours
+    pub above_left:  Option<&'static str>,
+    pub above_right: Option<&'static str>,
+    pub below_left:  Option<&'static str>,
+    pub below_right: Option<&'static str>,
 }
 
 impl Node {
     pub fn new(above: Option<&'static str>, left: Option<&'static str>, right: Option<&'static str>, below: Option<&'static str>) -> Self {
         Self {
-            state: State::Empty,
             above,
             left,
             right,
-            below
+            below,
+
+            above_left: None,
+            above_right: None,
+            below_left: None,
+            below_right: None
+        }
+    }
+
+    // Returns the same node with the given diagonal neighbours.
+    pub fn with_diagonals(self, above_left: Option<&'static str>, above_right: Option<&'static str>, below_left: Option<&'static str>, below_right: Option<&'static str>) -> Self {
+        Self {
+            above_left,
+            above_right,
+            below_left,
+            below_right,
+            ..self
         }
     }
 
     pub fn adjacent(&self) -> Vec<&str> {
-        let neighbours = vec![self.above, self.left, self.right, self.below];
+        let neighbours = [self.above, self.left, self.right, self.below, self.above_left, self.above_right, self.below_left, self.below_right];
 
-        neighbours.iter().filter(|elem| elem.is_some()).map(|elem| elem.unwrap()).collect()
+        neighbours.iter().flatten().copied().collect()
     }
 
     // Returns the neighbour in a given direction - None if there is no neighbour in that direction, or Some(position of the neighbour) if there is.
-    pub fn get_neighbour(&self, direction: Direction) -> Option<&str> {
+    pub fn get_neighbour(&self, direction: Direction) -> Option<&'static str> {
         match direction {
             Direction::Above => self.above,
             Direction::Left => self.left,
             Direction::Right => self.right,
-            Direction::Below => self.below
+            Direction::Below => self.below,
+
+            Direction::AboveLeft => self.above_left,
+            Direction::AboveRight => self.above_right,
+            Direction::BelowLeft => self.below_left,
+            Direction::BelowRight => self.below_right
         }
     }
-}
 
-use std::fmt::{self, Display, Formatter};
-impl Display for Node {
-    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        match self.state {
-            State::Empty                  => write!(f, "·"),
-            State::Occupied(Color::White) => write!(f, "○"),
-            State::Occupied(Color::Black) => write!(f, "●"),
+    // Returns the neighbour in a given direction for changing it (used when building a node from the list of its neighbours).
+    pub fn neighbour_mut(&mut self, direction: Direction) -> &mut Option<&'static str> {
+        match direction {
+            Direction::Above => &mut self.above,
+            Direction::Left => &mut self.left,
+            Direction::Right => &mut self.right,
+            Direction::Below => &mut self.below,
+
+            Direction::AboveLeft => &mut self.above_left,
+            Direction::AboveRight => &mut self.above_right,
+            Direction::BelowLeft => &mut self.below_left,
+            Direction::BelowRight => &mut self.below_right
         }
     }
 }
 
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -104,4 +130,20 @@ mod tests {
         assert_eq!(b4.get_neighbour(Direction::Right), Some("c4"));
         assert_eq!(b4.get_neighbour(Direction::Below), Some("b2"));
     }
+
+    #[test]
+    fn test_diagonals() {
+        let b6 = Node::new(None, None, Some("d6"), Some("b4")).with_diagonals(Some("a7"), None, None, Some("c5"));
+        assert_eq!(b6.adjacent(), vec!["d6", "b4", "a7", "c5"]);
+        assert_eq!(b6.get_neighbour(Direction::AboveLeft), Some("a7"));
+        assert_eq!(b6.get_neighbour(Direction::AboveRight), None);
+        assert_eq!(b6.get_neighbour(Direction::BelowLeft), None);
+        assert_eq!(b6.get_neighbour(Direction::BelowRight), Some("c5"));
+        assert_eq!(b6.get_neighbour(Direction::Right), Some("d6"));
+
+        let mut c5 = Node::new(None, None, Some("d5"), Some("c4"));
+        *c5.neighbour_mut(Direction::AboveLeft) = Some("b6");
+        assert_eq!(c5.get_neighbour(Direction::AboveLeft), Some("b6"));
+        assert_eq!(c5.adjacent(), vec!["d5", "c4", "b6"]);
+    }
 }
@@ -1,6 +1,7 @@
 use std::collections::HashSet;
 
 // stores information about the pieces of a player
+#[derive(Clone)]
 pub struct Pieces {
     pub unplaced: u8,
     pub placed: u8,
@@ -9,9 +10,10 @@ pub struct Pieces {
 }
 
 impl Pieces {
-    pub fn new() -> Self {
+    // a player with the given number of pieces, none of them placed yet
+    pub fn new(count: u8) -> Self {
         Self {
-            unplaced: 9,
+            unplaced: count,
             placed: 0,
 
             positions: HashSet::new()
@@ -38,6 +40,25 @@ impl Pieces {
         self.positions.remove(&from);
         self.positions.insert(to);
     }
+
+    // reverting changes to the pieces - these functions are called by Game.undo()
+    pub fn unplace_piece(&mut self, position: String) { // reverts place_piece()
+        if self.positions.remove(&position) {
+            self.unplaced += 1;
+            self.placed -= 1;
+        }
+    }
+    pub fn restore_piece(&mut self, position: String) { // reverts remove_piece()
+        if self.positions.insert(position) {
+            self.placed += 1;
+        }
+    }
+}
+
+impl Default for Pieces {
+    fn default() -> Self {
+        Self::new(9)
+    }
 }
 
 
@@ -47,7 +68,7 @@ mod tests {
 
     #[test]
     fn test_place() {
-        let mut pieces = Pieces::new();
+        let mut pieces = Pieces::new(9);
 
         assert_eq!(pieces.unplaced, 9);
         assert_eq!(pieces.placed, 0);
@@ -86,7 +107,7 @@ mod tests {
 
     #[test]
     fn test_remove() {
-        let mut pieces = Pieces::new();
+        let mut pieces = Pieces::new(9);
         pieces.place_piece("a7".to_string());
         assert_eq!(pieces.unplaced, 8);
         assert_eq!(pieces.placed, 1);
@@ -123,7 +144,7 @@ mod tests {
 
     #[test]
     fn test_move() {
-        let mut pieces = Pieces::new();
+        let mut pieces = Pieces::new(9);
         pieces.place_piece("a1".to_string());
         pieces.place_piece("d7".to_string());
         assert_eq!(pieces.unplaced, 7);
@@ -140,4 +161,29 @@ mod tests {
         assert_eq!(pieces.placed, 2);
         assert_eq!(pieces.positions, ["a7".to_string(), "d7".to_string()].iter().cloned().collect());
     }
+
+    #[test]
+    fn test_revert() {
+        let mut pieces = Pieces::new(9);
+        pieces.place_piece("a7".to_string());
+        pieces.place_piece("a4".to_string());
+
+        pieces.unplace_piece("a4".to_string());
+        assert_eq!(pieces.unplaced, 8);
+        assert_eq!(pieces.placed, 1);
+        assert_eq!(pieces.positions, ["a7".to_string()].iter().cloned().collect());
+
+        pieces.unplace_piece("d1".to_string());
+        assert_eq!(pieces.unplaced, 8);
+        assert_eq!(pieces.placed, 1);
+
+        pieces.remove_piece("a7".to_string());
+        pieces.restore_piece("a7".to_string());
+        assert_eq!(pieces.unplaced, 8);
+        assert_eq!(pieces.placed, 1);
+        assert_eq!(pieces.positions, ["a7".to_string()].iter().cloned().collect());
+
+        pieces.restore_piece("a7".to_string());
+        assert_eq!(pieces.placed, 1);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,270 @@
+use crate::game::Game;
+use crate::moves::Move;
+use crate::point::Point;
+use crate::ai::AlphaBeta;
+use crate::mcts::Mcts;
+use crate::rng::Rng;
+
+use std::collections::VecDeque;
+use std::io::{self, BufRead, Write};
+use std::ops::Deref;
+
+// A read-only view of a game, given to players so that they can inspect it (e.g. with legal_moves()) but not change it.
+pub struct GameView<'a> {
+    game: &'a Game
+}
+
+impl<'a> GameView<'a> {
+    pub fn new(game: &'a Game) -> Self {
+        Self { game }
+    }
+}
+
+impl Deref for GameView<'_> {
+    type Target = Game;
+
+    fn deref(&self) -> &Game {
+        self.game
+    }
+}
+
+// Something which chooses the moves of one of the players - a human, a script, or an engine.
+pub trait Player {
+    // Returns the move to play in the given game, whose current player is the one this player controls.
+    // Returning None means resigning.
+    fn choose_move(&mut self, game: &GameView) -> Option<Move>;
+}
+
+// A human player, who is asked to input their moves (e.g. "a7", "a7a4" or "a7-a4xd1") - an opponent's piece to remove is asked for separately if it's missing.
+// Invalid input is reported and asked for again; "resign" or the end of the input resigns.
+pub struct HumanPlayer<R: BufRead, W: Write> {
+    input: R,
+    output: W
+}
+
+impl<R: BufRead, W: Write> HumanPlayer<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Self { input, output }
+    }
+
+    // Writes the prompt and reads a single trimmed line - None at the end of the input.
+    fn ask(&mut self, prompt: &str) -> Option<String> {
+        writeln!(self.output, "{}", prompt).expect("ERROR: output error");
+        loop {
+            let mut line = String::new();
+            match self.input.read_line(&mut line) {
+                Err(error) => writeln!(self.output, "ERROR: input error - {}", error).expect("ERROR: output error"),
+                Ok(0) => return None,
+                Ok(_) => return Some(line.trim().to_string())
+            }
+        }
+    }
+
+    fn error(&mut self, message: String) {
+        writeln!(self.output, "ERROR: {}", message).expect("ERROR: output error");
+    }
+}
+
+impl HumanPlayer<io::StdinLock<'static>, io::Stdout> {
+    pub fn stdin() -> Self {
+        Self::new(io::stdin().lock(), io::stdout())
+    }
+}
+
+impl<R: BufRead, W: Write> Player for HumanPlayer<R, W> {
+    fn choose_move(&mut self, game: &GameView) -> Option<Move> {
+        let legal = game.legal_moves();
+        write!(self.output, "{}", **game).expect("ERROR: output error");
+
+        loop {
+            let input = self.ask(&format!("{} player, input your move:", game.current()))?;
+            if input == "resign" {
+                return None;
+            }
+
+            let mut m = match game.parse_move(&input) {
+                Ok(m) => m,
+                Err(_) => {
+                    self.error(format!("Invalid input - {}!", input));
+                    continue;
+                }
+            };
+            if !legal.iter().any(|legal| extends(legal, &m)) {
+                self.error(format!("Illegal move - {}!", input));
+                continue;
+            }
+
+            // ask for the captured pieces which weren't given with the move
+            while !legal.contains(&m) {
+                let input = self.ask(&format!("{} player FORMED A MILL! REMOVE opponent's piece:", game.current()))?;
+                match input.parse::<Point>() {
+                    Ok(capture) if legal.iter().any(|legal| extends(legal, &m.with_capture(capture))) => m = m.with_capture(capture),
+                    _ => self.error(format!("Cannot remove a piece from {}!", input))
+                }
+            }
+            return Some(m);
+        }
+    }
+}
+
+// Checks if the move is the same as the partial move, possibly with more captures.
+fn extends(m: &Move, partial: &Move) -> bool {
+    m.kind == partial.kind && m.captures().zip(partial.captures()).all(|(a, b)| a == b) && m.captures().count() >= partial.captures().count()
+}
+
+// A player which plays a fixed list of moves in order, and resigns once they run out.
+pub struct ScriptedPlayer {
+    moves: VecDeque<Move>
+}
+
+impl ScriptedPlayer {
+    pub fn new(moves: Vec<Move>) -> Self {
+        Self { moves: moves.into() }
+    }
+}
+
+impl Player for ScriptedPlayer {
+    fn choose_move(&mut self, _game: &GameView) -> Option<Move> {
+        self.moves.pop_front()
+    }
+}
+
+// A player which plays a uniformly random legal move - the same seed gives the same choices.
+pub struct RandomPlayer {
+    rng: Rng
+}
+
+impl RandomPlayer {
+    pub fn new(seed: u64) -> Self {
+        Self { rng: Rng::new(seed) }
+    }
+}
+
+impl Player for RandomPlayer {
+    fn choose_move(&mut self, game: &GameView) -> Option<Move> {
+        let moves = game.legal_moves();
+        if moves.is_empty() {
+            return None;
+        }
+
+        Some(moves[self.rng.below(moves.len())])
+    }
+}
+
+impl Player for AlphaBeta {
+    fn choose_move(&mut self, game: &GameView) -> Option<Move> {
+        self.best_move(game)
+    }
+}
+
+impl Player for Mcts {
+    fn choose_move(&mut self, game: &GameView) -> Option<Move> {
+        self.best_move(game)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enums::{Color, GameResult, WinReason};
+    use crate::rules::{Rules, DoubleMill};
+
+    fn moves(moves: &[&str]) -> Vec<Move> {
+        moves.iter().map(|m| m.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn test_scripted() {
+        let mut white = ScriptedPlayer::new(moves(&["a7", "d7", "g7xd1"]));
+        let mut black = ScriptedPlayer::new(moves(&["a1", "d1"]));
+
+        let mut game = Game::default();
+        let result = game.play(&mut white, &mut black);
+        assert_eq!(result, GameResult::Win { winner: Color::White, reason: WinReason::Resignation });
+        assert_eq!(game.history(), &moves(&["a7", "a1", "d7", "d1", "g7xd1"])[..]);
+    }
+
+    #[test]
+    fn test_illegal() {
+        let mut white = ScriptedPlayer::new(moves(&["a7", "a1"]));
+        let mut black = ScriptedPlayer::new(moves(&["a7"]));
+
+        let mut game = Game::default();
+        let result = game.play(&mut white, &mut black);
+        assert_eq!(result, GameResult::Win { winner: Color::White, reason: WinReason::IllegalMove });
+        assert_eq!(game.history().len(), 1);
+    }
+
+    #[test]
+    fn test_random() {
+        let play = |seed| {
+            let mut game = Game::default();
+            let result = game.play(&mut RandomPlayer::new(seed), &mut RandomPlayer::new(seed + 1));
+            (result, game.history().to_vec())
+        };
+
+        let (result, history) = play(1);
+        assert!(matches!(result, GameResult::Win { reason: WinReason::TooFewPieces, .. } | GameResult::Win { reason: WinReason::Blocked, .. }));
+        assert_eq!(play(1), (result, history));
+    }
+
+    #[test]
+    fn test_engine() {
+        let mut game = Game::default();
+        let result = game.play(&mut AlphaBeta::new(2), &mut RandomPlayer::new(3));
+        assert!(matches!(result, GameResult::Win { winner: Color::White, .. }));
+
+        let mut game = Game::default();
+        let result = game.play(&mut RandomPlayer::new(3), &mut Mcts::new(200, 3));
+        assert!(matches!(result, GameResult::Win { winner: Color::Black, .. }));
+    }
+
+    #[test]
+    fn test_human() {
+        let mut game = Game::default();
+        for m in ["a7", "a1", "d7", "d1"].iter() {
+            game.make_move(&m.parse().unwrap()).unwrap();
+        }
+
+        let input = &b"a8\nd1\ng7\nd7\nd1\n"[..];
+        let mut output = Vec::new();
+        let m = HumanPlayer::new(input, &mut output).choose_move(&GameView::new(&game));
+        assert_eq!(m, Some("g7xd1".parse().unwrap()));
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("ERROR: Illegal move - a8!"));
+        assert!(output.contains("ERROR: Illegal move - d1!"));
+        assert!(output.contains("ERROR: Cannot remove a piece from d7!"));
+
+        let m = HumanPlayer::new(&b"resign\n"[..], Vec::new()).choose_move(&GameView::new(&game));
+        assert_eq!(m, None);
+        let m = HumanPlayer::new(&b""[..], Vec::new()).choose_move(&GameView::new(&game));
+        assert_eq!(m, None);
+        let m = HumanPlayer::new(&b"a7-\ng7\n"[..], Vec::new()).choose_move(&GameView::new(&game));
+        assert_eq!(m, None);
+
+        // a player who can fly moves to any empty position
+        let game = Game::from_notation("WW1/BWB/B11 w m 0 0", Rules { flying: true, ..Rules::three_mens_morris() }).unwrap();
+        let m = HumanPlayer::new(&b"a3c3\n"[..], Vec::new()).choose_move(&GameView::new(&game));
+        assert_eq!(m, Some(Move::fly("a3".parse().unwrap(), "c3".parse().unwrap())));
+
+        // including to an adjacent one, written as a step
+        let mut output = Vec::new();
+        let m = HumanPlayer::new(&b"b3-c3\n"[..], &mut output).choose_move(&GameView::new(&game));
+        assert_eq!(m, Some(Move::fly("b3".parse().unwrap(), "c3".parse().unwrap())));
+        assert!(!String::from_utf8(output).unwrap().contains("ERROR"));
+    }
+    #[test]
+    fn test_human_double_mill() {
+        let mut game = Game::new(Rules { double_mill: DoubleMill::TwoCaptures, ..Rules::default() });
+        for m in ["a7", "d7", "a1", "d1", "b4", "g7", "c4", "g1"].iter() {
+            game.make_move(&m.parse().unwrap()).unwrap();
+        }
+
+        let m = HumanPlayer::new(&b"a4xd7\nd7\ng1\n"[..], Vec::new()).choose_move(&GameView::new(&game));
+        assert_eq!(m, Some("a4xd7xg1".parse().unwrap()));
+        let m = HumanPlayer::new(&b"a4xd7xg1\n"[..], Vec::new()).choose_move(&GameView::new(&game));
+        assert_eq!(m, Some("a4xd7xg1".parse().unwrap()));
+    }
+}
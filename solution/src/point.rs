@@ -0,0 +1,73 @@
+use crate::errors::ParseMoveError;
+
+use std::str::FromStr;
+
+// the coordinates of a position on the board, e.g. a7 - a letter for the column and a number for the row
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub struct Point {
+    pub file: u8, // column, 0 for 'a'
+    pub rank: u8  // row, as printed on the board (starting from 1)
+}
+
+impl Point {
+    pub fn new(file: u8, rank: u8) -> Self {
+        Self { file, rank }
+    }
+}
+
+impl FromStr for Point {
+    type Err = ParseMoveError;
+
+    // Parses a letter followed by a number, e.g. "a7" - any other input results in an error.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let file = match chars.next() {
+            Some(letter) if letter.is_ascii_lowercase() => letter as u8 - b'a',
+            _ => return Err(ParseMoveError::InvalidPosition)
+        };
+
+        let digits = chars.as_str();
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(ParseMoveError::InvalidPosition);
+        }
+        match digits.parse::<u8>() {
+            Ok(rank) if rank > 0 => Ok(Self { file, rank }),
+            _ => Err(ParseMoveError::InvalidPosition)
+        }
+    }
+}
+
+use std::fmt::{self, Display, Formatter};
+impl Display for Point {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}{}", (b'a' + self.file) as char, self.rank)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("a7".parse(), Ok(Point::new(0, 7)));
+        assert_eq!("d1".parse(), Ok(Point::new(3, 1)));
+        assert_eq!("g12".parse(), Ok(Point::new(6, 12)));
+
+        assert_eq!("".parse::<Point>(), Err(ParseMoveError::InvalidPosition));
+        assert_eq!("a".parse::<Point>(), Err(ParseMoveError::InvalidPosition));
+        assert_eq!("7a".parse::<Point>(), Err(ParseMoveError::InvalidPosition));
+        assert_eq!("a0".parse::<Point>(), Err(ParseMoveError::InvalidPosition));
+        assert_eq!("A7".parse::<Point>(), Err(ParseMoveError::InvalidPosition));
+        assert_eq!("a+7".parse::<Point>(), Err(ParseMoveError::InvalidPosition));
+        assert_eq!("а7".parse::<Point>(), Err(ParseMoveError::InvalidPosition));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Point::new(0, 7).to_string(), "a7");
+        assert_eq!(Point::new(6, 1).to_string(), "g1");
+        assert_eq!("c10".parse::<Point>().unwrap().to_string(), "c10");
+    }
+}
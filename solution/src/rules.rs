@@ -0,0 +1,101 @@
+use crate::layout::Layout;
+
+use std::sync::Arc;
+
+// The rules of a game which differ between variants and house rules. The default is standard nine men's morris.
+#[derive(Clone, Debug)]
+pub struct Rules {
+    pub layout: Arc<Layout>,        // the board the game is played on
+    pub pieces: u8,                 // the number of pieces each player starts with
+    pub flying: bool,               // whether a player with only 3 pieces left can move them to any empty position
+    pub protect_mills: bool,        // whether pieces in a mill can only be removed when all of the player's pieces are in mills
+    pub double_mill: DoubleMill,    // what happens when a single move forms two mills at once
+    pub interleaved: bool,          // whether a player can choose between placing and moving a piece on each turn, until all pieces are placed
+    pub reform_mills: bool,         // whether a mill broken by moving a piece out of it captures again when the piece moves straight back on the next turn
+    pub draw: DrawRules
+}
+
+impl Rules {
+    // three men's morris - 3 pieces each, on a 3x3 grid with diagonals. Forming a mill leaves the opponent with too few pieces, so it wins the game
+    pub fn three_mens_morris() -> Self {
+        Self { layout: Layout::three_mens_morris(), pieces: 3, flying: false, ..Self::default() }
+    }
+    // six men's morris - 6 pieces each, on a board of two squares, without flying
+    pub fn six_mens_morris() -> Self {
+        Self { layout: Layout::six_mens_morris(), pieces: 6, flying: false, ..Self::default() }
+    }
+    // Lasker morris - nine men's morris with 10 pieces each, where a player can choose to place a piece or move one on every turn
+    pub fn lasker_morris() -> Self {
+        Self { pieces: 10, interleaved: true, ..Self::default() }
+    }
+    // twelve men's morris - 12 pieces each, on a board with diagonal lines (which also form mills)
+    pub fn twelve_mens_morris() -> Self {
+        Self { layout: Layout::twelve_mens_morris(), pieces: 12, ..Self::default() }
+    }
+    // Morabaraba - twelve men's morris, except that a mill which was just broken can't be re-formed for a capture on the very next turn
+    pub fn morabaraba() -> Self {
+        Self { reform_mills: false, ..Self::twelve_mens_morris() }
+    }
+
+    // Returns the rules of the variant with the given short name (as chosen on the command line), if there is one.
+    pub fn variant(name: &str) -> Option<Self> {
+        match name {
+            "three" => Some(Self::three_mens_morris()),
+            "six" => Some(Self::six_mens_morris()),
+            "nine" => Some(Self::default()),
+            "twelve" => Some(Self::twelve_mens_morris()),
+            "morabaraba" => Some(Self::morabaraba()),
+            "lasker" => Some(Self::lasker_morris()),
+            _ => None
+        }
+    }
+
+    // Checks if the board has room for all the pieces of both players - otherwise the placing phase could run out of empty positions,
+    // leaving the current player without a legal move before the game has ended.
+    pub fn fits(&self) -> bool {
+        self.pieces as usize * 2 <= self.layout.len()
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            layout: Layout::nine_mens_morris(),
+            pieces: 9,
+            flying: true,
+            protect_mills: true,
+            double_mill: DoubleMill::OneCapture,
+            interleaved: false,
+            reform_mills: true,
+            draw: DrawRules::default()
+        }
+    }
+}
+
+// what happens when a single move forms two mills at once
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DoubleMill {
+    OneCapture,  // only one of the opponent's pieces is removed, as for a single mill
+    TwoCaptures  // two of the opponent's pieces are removed, one after another
+}
+
+// The optional rules which end a game in a draw when neither player can make progress.
+// Either of them can be turned off by setting it to None.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DrawRules {
+    pub repetitions: Option<u32>,        // the game is drawn when the same position (with the same side to move) occurs this many times
+    pub moves_without_mill: Option<u32>  // the game is drawn after this many moves in the moving phase (by either player) without a mill
+}
+
+impl DrawRules {
+    // no draws other than both players being blocked
+    pub fn none() -> Self {
+        Self { repetitions: None, moves_without_mill: None }
+    }
+}
+
+impl Default for DrawRules {
+    fn default() -> Self {
+        Self { repetitions: Some(3), moves_without_mill: Some(50) }
+    }
+}
@@ -0,0 +1,138 @@
+use crate::layout::Layout;
+use crate::point::Point;
+
+// One of the 16 candidate symmetries of a board: the inner and outer squares are swapped (if swap_squares is set),
+// then the board is reflected left to right (if reflect is set), and finally rotated counterclockwise by rotation * 90 degrees.
+// Which of them actually are symmetries depends on the layout (see Layout.symmetries()) - e.g. all 16 for nine men's morris,
+// but only the 8 without swapping squares for three men's morris, which has a single square around its center.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Transform {
+    pub rotation: u8,       // 0 to 3
+    pub reflect: bool,
+    pub swap_squares: bool
+}
+
+impl Transform {
+    pub const IDENTITY: Self = Self { rotation: 0, reflect: false, swap_squares: false };
+
+    // all 16 transforms, starting with the identity
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..16u8).map(|i| Self { rotation: i % 4, reflect: i & 4 != 0, swap_squares: i & 8 != 0 })
+    }
+
+    // Returns the transform which undoes this one.
+    pub fn inverse(self) -> Self {
+        // a reflection followed by a rotation is its own inverse, so only plain rotations change
+        if self.reflect {
+            self
+        } else {
+            Self { rotation: (4 - self.rotation) % 4, ..self }
+        }
+    }
+
+    // Maps coordinates relative to the center of the board, given the sizes of its squares (the distances of their sides from the center,
+    // from the innermost to the outermost). Returns None if the point doesn't land on whole coordinates.
+    fn map(self, (x, y): (i32, i32), squares: &[i32]) -> Option<(i32, i32)> {
+        let (mut x, mut y) = (x, y);
+
+        if self.swap_squares {
+            let size = x.abs().max(y.abs());
+            let square = squares.iter().position(|s| *s == size)?;
+            let swapped = squares[squares.len() - 1 - square];
+            if size != swapped {
+                if size == 0 || x * swapped % size != 0 || y * swapped % size != 0 {
+                    return None;
+                }
+                x = x * swapped / size;
+                y = y * swapped / size;
+            }
+        }
+        if self.reflect {
+            x = -x;
+        }
+        for _ in 0..self.rotation {
+            (x, y) = (-y, x);
+        }
+
+        Some((x, y))
+    }
+}
+
+// Finds which of the 16 transforms are symmetries of the layout - the ones which map every position to a position,
+// adjacent positions to adjacent positions and mills to mills. Each is returned with the index every position is mapped to.
+pub fn symmetries(layout: &Layout) -> Vec<(Transform, Vec<u8>)> {
+    if layout.is_empty() {
+        return vec![(Transform::IDENTITY, Vec::new())];
+    }
+
+    // coordinates are doubled, so that the center of the board is on whole coordinates even if the board is an even number of points wide
+    let points: Vec<Point> = (0..layout.len()).map(|i| layout.point(i)).collect();
+    let (files, ranks): (Vec<i32>, Vec<i32>) = points.iter().map(|point| (point.file as i32, point.rank as i32)).unzip();
+    let center = (files.iter().min().unwrap_or(&0) + files.iter().max().unwrap_or(&0), ranks.iter().min().unwrap_or(&0) + ranks.iter().max().unwrap_or(&0));
+    let coordinates: Vec<(i32, i32)> = points.iter().map(|point| (2 * point.file as i32 - center.0, 2 * point.rank as i32 - center.1)).collect();
+
+    let mut squares: Vec<i32> = coordinates.iter().map(|(x, y)| x.abs().max(y.abs())).collect();
+    squares.sort_unstable();
+    squares.dedup();
+
+    let index = |(x, y): (i32, i32)| coordinates.iter().position(|c| *c == (x, y));
+    let permute = |mask: u32, permutation: &[u8]| (0..layout.len()).filter(|i| mask & 1 << i != 0).fold(0u32, |result, i| result | 1 << permutation[i]);
+
+    let mut result = Vec::new();
+    for transform in Transform::all() {
+        let permutation: Option<Vec<u8>> = coordinates.iter().map(|c| transform.map(*c, &squares).and_then(index).map(|i| i as u8)).collect();
+        let permutation = match permutation {
+            Some(permutation) => permutation,
+            None => continue
+        };
+
+        let mut mills: Vec<u32> = layout.mills().iter().map(|mill| permute(*mill, &permutation)).collect();
+        mills.sort_unstable();
+        let mut expected = layout.mills().to_vec();
+        expected.sort_unstable();
+
+        let bijective = permute(layout.all(), &permutation) == layout.all();
+        let adjacency = (0..layout.len()).all(|i| permute(layout.adjacent(i), &permutation) == layout.adjacent(permutation[i] as usize));
+        if bijective && adjacency && mills == expected {
+            result.push((transform, permutation));
+        }
+    }
+    result
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inverse() {
+        let squares = [2, 4, 6];
+        for transform in Transform::all() {
+            let inverse = transform.inverse();
+            for point in [(0, 6), (-6, 6), (2, 0), (-4, -4)].iter() {
+                assert_eq!(transform.map(*point, &squares).and_then(|p| inverse.map(p, &squares)), Some(*point));
+            }
+        }
+        assert_eq!(Transform::all().count(), 16);
+        assert_eq!(Transform::all().next(), Some(Transform::IDENTITY));
+    }
+
+    #[test]
+    fn test_map() {
+        let squares = [2, 4, 6];
+        let rotate = Transform { rotation: 1, ..Transform::IDENTITY };
+        assert_eq!(rotate.map((6, 6), &squares), Some((-6, 6)));
+        assert_eq!(rotate.map((0, 6), &squares), Some((-6, 0)));
+
+        let reflect = Transform { reflect: true, ..Transform::IDENTITY };
+        assert_eq!(reflect.map((6, 0), &squares), Some((-6, 0)));
+
+        let swap = Transform { swap_squares: true, ..Transform::IDENTITY };
+        assert_eq!(swap.map((6, 6), &squares), Some((2, 2)));
+        assert_eq!(swap.map((0, -2), &squares), Some((0, -6)));
+        assert_eq!(swap.map((4, 0), &squares), Some((4, 0)));
+        assert_eq!(swap.map((0, 0), &[0, 2]), None);
+        assert_eq!(swap.map((3, 1), &[1, 3]), None);
+    }
+}
@@ -0,0 +1,711 @@
+use crate::board::Board;
+use crate::game::Game;
+use crate::enums::{Color, Phase};
+use crate::errors::TablebaseError;
+use crate::rules::{Rules, DoubleMill};
+
+use std::convert::TryInto;
+use std::sync::OnceLock;
+
+// The value of a position for the player to move when both players play perfectly - a win or a loss in the given number of plies
+// (turns, including the capture after a mill), or a draw if neither player can force a win.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Value {
+    Win(u16),
+    Loss(u16),
+    Draw
+}
+
+impl Value {
+    fn encode(self) -> u16 {
+        match self {
+            Value::Draw => 0,
+            Value::Win(distance) => 0x4000 | distance,
+            Value::Loss(distance) => 0x8000 | distance
+        }
+    }
+
+    fn decode(value: u16) -> Option<Self> {
+        match value & 0xC000 {
+            0 if value == 0 => Some(Value::Draw),
+            0x4000 => Some(Value::Win(value & 0x3FFF)),
+            0x8000 => Some(Value::Loss(value & 0x3FFF)),
+            _ => None
+        }
+    }
+}
+
+// the solved positions with the given numbers of pieces on the board
+struct Table {
+    white: u8,
+    black: u8,
+    ranks: Vec<u32>,        // the ranks of the canonical positions (see Tablebase.rank()), in increasing order
+    values: [Vec<Value>; 2] // the value of each of the positions, with white and with black to move
+}
+
+// An endgame tablebase - the exact values of all positions of the moving phase with up to a given number of pieces of each player,
+// found by retrograde analysis: starting from the positions which are lost right away, and working backwards through the moves leading to them.
+// Only one of the symmetric versions of each position (its canonical board, see Board.canonical()) is stored.
+// The rules which depend on the history of the game (draws by repetition or without mills, and Rules.reform_mills) aren't taken into account,
+// so a draw means that neither player can force a win.
+pub struct Tablebase {
+    board: Board,           // an empty board with the layout of the rules, for its mill checks
+    rules: Rules,
+    symmetries: Vec<Vec<u8>>, // the index each position is mapped to, for every symmetry of the layout
+    tables: Vec<Table>
+}
+
+// an event in the retrograde analysis - a position whose value became known, or a move out of the table with a known value
+enum Event {
+    Solved(usize),
+    Exit(usize, Value) // a move from the given position to a position in a smaller table (or which ends the game)
+}
+
+// The state of the retrograde analysis of a table, where every position with either player to move is a node.
+// A node is won as soon as one of its moves leads to a lost node - processing the events in order of distance finds the quickest win.
+// It's lost when all of its moves lead to won nodes, which happens at the distance of the slowest of them.
+struct Retrograde {
+    values: Vec<Option<Value>>,
+    remaining: Vec<u32>,        // the moves from each node which aren't known to lead to a won node yet (not counting moves out of the table to draws)
+    draw_exit: Vec<bool>,       // whether a move from the node leads out of the table to a drawn position, so it can't be lost
+    events: Vec<Vec<Event>>     // the events at each distance, processed in increasing order
+}
+
+impl Retrograde {
+    // Updates the node before a move, given the value of the position after it (for the opponent).
+    fn resolve(&mut self, node: usize, value: Value) {
+        if self.values[node].is_some() {
+            return;
+        }
+
+        match value {
+            Value::Loss(distance) => {
+                self.values[node] = Some(Value::Win(distance + 1));
+                push(&mut self.events, distance + 1, Event::Solved(node));
+            }
+            Value::Win(distance) => {
+                self.remaining[node] -= 1;
+                if self.remaining[node] == 0 && !self.draw_exit[node] {
+                    self.values[node] = Some(Value::Loss(distance + 1));
+                    push(&mut self.events, distance + 1, Event::Solved(node));
+                }
+            }
+            Value::Draw => {}
+        }
+    }
+}
+
+const MAGIC: &[u8; 8] = b"MORRISTB";
+
+impl Tablebase {
+    // Solves all positions of the moving phase with 3 to the given number of pieces of each player, under the given rules.
+    // Positions with more pieces are only reached through positions with fewer, so the smaller tables are solved first.
+    // Panics if the tables wouldn't be supported (see Tablebase::supports()).
+    pub fn generate(rules: &Rules, white: u8, black: u8) -> Self {
+        assert!(white as usize + black as usize <= rules.layout.len(), "ERROR: the pieces don't fit on the board");
+        assert!(Self::supports(rules, white, black), "ERROR: too many positions to rank in 32 bits");
+
+        let mut tablebase = Self::empty(rules);
+        let mut sizes: Vec<(u8, u8)> = (3..=white).flat_map(|w| (3..=black).map(move |b| (w, b))).collect();
+        sizes.sort_by_key(|(w, b)| w + b);
+
+        for (w, b) in sizes {
+            let table = tablebase.solve(w, b);
+            tablebase.tables.push(table);
+        }
+        tablebase
+    }
+
+    // Checks if the tables with up to the given numbers of pieces can be generated - the pieces have to fit on the board,
+    // and every position has to have a rank which fits in 32 bits (which rules out e.g. 7 pieces against 7 on a board of 24 points).
+    pub fn supports(rules: &Rules, white: u8, black: u8) -> bool {
+        let length = rules.layout.len();
+        if white as usize + black as usize > length {
+            return false;
+        }
+        (3..=white as usize).all(|w| (3..=black as usize).all(|b| binomial(length, w) * binomial(length - w, b) <= u32::MAX as u64 + 1))
+    }
+
+    fn empty(rules: &Rules) -> Self {
+        let layout = &rules.layout;
+        let symmetries = layout.symmetries()
+            .map(|transform| (0..layout.len()).map(|i| layout.transform_index(transform, i).unwrap_or(i) as u8).collect())
+            .collect();
+
+        Self { board: Board::with_layout(layout.clone()), rules: rules.clone(), symmetries, tables: Vec::new() }
+    }
+
+    // Returns the value of the game's position for its current player - None if it isn't in the tablebase
+    // (e.g. in the placing phase, with more pieces than the tablebase was generated for, or when the game is played by other rules).
+    pub fn probe(&self, game: &Game) -> Option<Value> {
+        if game.phase() != Phase::Moving || game.removal_pending() {
+            return None;
+        }
+
+        let board = game.board();
+        let (white, black) = (board.occupied(Color::White), board.occupied(Color::Black));
+        if self.table(white.count_ones() as u8, black.count_ones() as u8).is_none() || signature(game.rules()) != signature(&self.rules) {
+            return None;
+        }
+        self.probe_position(white, black, game.current())
+    }
+
+    // Returns the value of the position with the given masks of white and black pieces, for the given player to move.
+    pub fn probe_position(&self, white: u32, black: u32, current: Color) -> Option<Value> {
+        let table = self.table(white.count_ones() as u8, black.count_ones() as u8)?;
+        let (white, black) = self.canonical(white, black);
+        let index = table.ranks.binary_search(&self.rank(white, black)).ok()?;
+
+        Some(table.values[side(current)][index])
+    }
+
+    // the number of positions stored (each with both players to move), with the given numbers of pieces
+    pub fn len(&self, white: u8, black: u8) -> usize {
+        self.table(white, black).map_or(0, |table| table.ranks.len())
+    }
+
+    fn table(&self, white: u8, black: u8) -> Option<&Table> {
+        self.tables.iter().find(|table| table.white == white && table.black == black)
+    }
+}
+
+impl Tablebase { // retrograde analysis
+    fn solve(&self, white: u8, black: u8) -> Table {
+        let positions = self.canonical_positions(white, black);
+        let count = positions.len() * 2; // every position with white (side 0) and black (side 1) to move is a node
+
+        let mut values: Vec<Option<Value>> = vec![None; count];
+        let mut remaining = vec![0u32; count];
+        let mut draw_exit = vec![false; count];
+        let mut edges: Vec<(u32, u32)> = Vec::new(); // (node after a move, node before it) for moves within the table
+        let mut events: Vec<Vec<Event>> = vec![Vec::new()];
+
+        let mut successors = Vec::new();
+        let mut inside = Vec::new();
+        for (i, &(_, white_mask, black_mask)) in positions.iter().enumerate() {
+            for color in [Color::White, Color::Black].iter() {
+                let node = 2 * i + side(*color);
+                let (own, opponent) = if *color == Color::White { (white_mask, black_mask) } else { (black_mask, white_mask) };
+                if let Some(value) = self.terminal(own, opponent) {
+                    values[node] = Some(value);
+                    if value != Value::Draw {
+                        events[0].push(Event::Solved(node));
+                    }
+                    continue;
+                }
+
+                successors.clear();
+                self.successors(own, opponent, &mut successors);
+
+                inside.clear();
+                for &(own_after, opponent_after) in successors.iter() {
+                    let (white_after, black_after) = if *color == Color::White { (own_after, opponent_after) } else { (opponent_after, own_after) };
+
+                    let value = if opponent_after.count_ones() < 3 {
+                        Value::Loss(0) // the opponent has too few pieces left
+                    } else if opponent_after == opponent {
+                        let (white_after, black_after) = self.canonical(white_after, black_after);
+                        let rank = self.rank(white_after, black_after);
+                        let index = positions.binary_search_by_key(&rank, |(rank, _, _)| *rank).expect("ERROR: position missing from the table");
+                        inside.push((2 * index + side(color.other())) as u32);
+                        continue;
+                    } else {
+                        self.probe_position(white_after, black_after, color.other()).expect("ERROR: the smaller tables have to be solved first")
+                    };
+
+                    match value {
+                        Value::Draw => draw_exit[node] = true,
+                        Value::Win(distance) | Value::Loss(distance) => {
+                            remaining[node] += 1;
+                            push(&mut events, distance, Event::Exit(node, value));
+                        }
+                    }
+                }
+
+                inside.sort_unstable();
+                inside.dedup();
+                remaining[node] += inside.len() as u32;
+                edges.extend(inside.iter().map(|&after| (after, node as u32)));
+            }
+        }
+
+        // the moves leading to each node, grouped by the node (the ones leading to node n are predecessors[offsets[n]..offsets[n + 1]])
+        edges.sort_unstable();
+        let mut offsets = vec![0usize; count + 1];
+        for &(after, _) in edges.iter() {
+            offsets[after as usize + 1] += 1;
+        }
+        for n in 0..count {
+            offsets[n + 1] += offsets[n];
+        }
+        let predecessors: Vec<u32> = edges.into_iter().map(|(_, before)| before).collect();
+
+        let mut analysis = Retrograde { values, remaining, draw_exit, events };
+        let mut distance = 0;
+        while distance < analysis.events.len() {
+            for event in std::mem::take(&mut analysis.events[distance]) {
+                match event {
+                    Event::Exit(node, value) => analysis.resolve(node, value),
+                    Event::Solved(solved) => {
+                        let value = analysis.values[solved].expect("ERROR: unsolved event");
+                        for &node in predecessors[offsets[solved]..offsets[solved + 1]].iter() {
+                            analysis.resolve(node as usize, value);
+                        }
+                    }
+                }
+            }
+            distance += 1;
+        }
+        let values = analysis.values;
+
+        // whatever is left can't be forced to either side
+        let value = |color: Color| (0..positions.len()).map(|i| values[2 * i + side(color)].unwrap_or(Value::Draw)).collect();
+        Table {
+            white,
+            black,
+            ranks: positions.iter().map(|(rank, _, _)| *rank).collect(),
+            values: [value(Color::White), value(Color::Black)]
+        }
+    }
+
+    // Returns the canonical positions with the given numbers of pieces - their ranks and masks, ordered by rank.
+    fn canonical_positions(&self, white: u8, black: u8) -> Vec<(u32, u32, u32)> {
+        let all = self.board.layout().all();
+        let mut positions = Vec::new();
+        for white_mask in subsets(all, white) {
+            for black_mask in subsets(all & !white_mask, black) {
+                if self.canonical(white_mask, black_mask) == (white_mask, black_mask) {
+                    positions.push((self.rank(white_mask, black_mask), white_mask, black_mask));
+                }
+            }
+        }
+
+        positions.sort_unstable();
+        positions
+    }
+
+    // Returns the value of a position which has already ended (see Game.outcome()) for the player to move - a player who can't move loses,
+    // and if neither of them can, it's a draw. Returns None if both players can move.
+    fn terminal(&self, own: u32, opponent: u32) -> Option<Value> {
+        match (self.can_move(own, opponent), self.can_move(opponent, own)) {
+            (true, true) => None,
+            (true, false) => Some(Value::Win(0)),
+            (false, true) => Some(Value::Loss(0)),
+            (false, false) => Some(Value::Draw)
+        }
+    }
+
+    fn can_move(&self, pieces: u32, others: u32) -> bool {
+        let board = &self.board;
+        let empty = board.layout().all() & !(pieces | others);
+        if self.flying(pieces) {
+            return empty != 0;
+        }
+        board.indices(pieces).any(|index| board.layout().adjacent(index) & empty != 0)
+    }
+
+    fn flying(&self, pieces: u32) -> bool {
+        self.rules.flying && pieces.count_ones() <= 3
+    }
+
+    // Lists the positions after every legal move of the player with the given pieces, as (the player's pieces, the opponent's pieces) -
+    // the same moves as Game.legal_moves() in the moving phase.
+    fn successors(&self, own: u32, opponent: u32, successors: &mut Vec<(u32, u32)>) {
+        let board = &self.board;
+        let empty = board.layout().all() & !(own | opponent);
+        let flying = self.flying(own);
+        let allowed = match self.rules.double_mill {
+            DoubleMill::OneCapture => 1,
+            DoubleMill::TwoCaptures => 2
+        };
+
+        for from in board.indices(own) {
+            let destinations = if flying { empty } else { board.layout().adjacent(from) & empty };
+            for to in board.indices(destinations) {
+                let after = own ^ (1 << from | 1 << to);
+
+                match board.mills_with(after, to).min(allowed).min(opponent.count_ones() as usize) {
+                    0 => successors.push((after, opponent)),
+                    1 => successors.extend(board.indices(self.capturable(opponent)).map(|capture| (after, opponent & !(1 << capture)))),
+                    _ => {
+                        for first in board.indices(self.capturable(opponent)) {
+                            let remaining = opponent & !(1 << first);
+                            successors.extend(board.indices(self.capturable(remaining)).map(|second| (after, remaining & !(1 << second))));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // a mask of the opponent's pieces (given as a mask) which can be removed - see Game.capturable()
+    fn capturable(&self, opponent: u32) -> u32 {
+        let in_mills = self.board.in_mills_with(opponent);
+        if !self.rules.protect_mills || in_mills == opponent { opponent } else { opponent & !in_mills }
+    }
+
+    // the symmetric version of the position with the smallest masks - the same as Board.canonical(), without building the boards
+    fn canonical(&self, white: u32, black: u32) -> (u32, u32) {
+        let permute = |permutation: &[u8], mask: u32| self.board.indices(mask).fold(0u32, |result, i| result | 1 << permutation[i]);
+
+        self.symmetries.iter()
+            .map(|permutation| (permute(permutation, white), permute(permutation, black)))
+            .min()
+            .unwrap_or((white, black))
+    }
+
+    // The number of the position among all positions with the same numbers of pieces: the rank of the white pieces among all combinations
+    // of that many positions, combined with the rank of the black pieces among the positions left empty by white.
+    fn rank(&self, white: u32, black: u32) -> u32 {
+        let length = self.board.layout().len();
+
+        let mut compressed = 0u32; // the black pieces, numbered among the positions not occupied by white
+        let mut j = 0;
+        for i in 0..length {
+            if white & 1 << i == 0 {
+                compressed |= (black >> i & 1) << j;
+                j += 1;
+            }
+        }
+
+        let combinations = binomial(length - white.count_ones() as usize, black.count_ones() as usize);
+        (colex_rank(white) * combinations + colex_rank(compressed)) as u32
+    }
+}
+
+impl Tablebase { // reading and writing
+    // Writes the tablebase in a compact binary format: a header identifying the rules, followed by each table -
+    // the numbers of pieces, the number of positions, their ranks and their values (2 bytes each) with white and with black to move.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&signature(&self.rules).to_le_bytes());
+        bytes.extend_from_slice(&(self.tables.len() as u32).to_le_bytes());
+
+        for table in self.tables.iter() {
+            bytes.push(table.white);
+            bytes.push(table.black);
+            bytes.extend_from_slice(&(table.ranks.len() as u32).to_le_bytes());
+            for rank in table.ranks.iter() {
+                bytes.extend_from_slice(&rank.to_le_bytes());
+            }
+            for value in table.values.iter().flatten() {
+                bytes.extend_from_slice(&value.encode().to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    // Reads a tablebase written by to_bytes(), which has to have been generated for the given rules.
+    pub fn from_bytes(bytes: &[u8], rules: &Rules) -> Result<Self, TablebaseError> {
+        let mut reader = Reader { bytes };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(TablebaseError::InvalidFormat);
+        }
+        if reader.u64()? != signature(rules) {
+            return Err(TablebaseError::RulesMismatch);
+        }
+
+        let mut tablebase = Self::empty(rules);
+        for _ in 0..reader.u32()? {
+            let white = reader.take(1)?[0];
+            let black = reader.take(1)?[0];
+            let count = reader.u32()? as usize;
+
+            let ranks = (0..count).map(|_| reader.u32()).collect::<Result<Vec<_>, _>>()?;
+            let mut values = Vec::with_capacity(2 * count);
+            for _ in 0..2 * count {
+                values.push(Value::decode(reader.u16()?).ok_or(TablebaseError::InvalidFormat)?);
+            }
+            let black_to_move = values.split_off(count);
+
+            if ranks.windows(2).any(|pair| pair[0] >= pair[1]) {
+                return Err(TablebaseError::InvalidFormat);
+            }
+            tablebase.tables.push(Table { white, black, ranks, values: [values, black_to_move] });
+        }
+
+        if reader.bytes.is_empty() { Ok(tablebase) } else { Err(TablebaseError::InvalidFormat) }
+    }
+}
+
+// reads little-endian numbers from the start of a slice, failing if it's too short
+struct Reader<'a> {
+    bytes: &'a [u8]
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], TablebaseError> {
+        if self.bytes.len() < length {
+            return Err(TablebaseError::InvalidFormat);
+        }
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(taken)
+    }
+    fn u16(&mut self) -> Result<u16, TablebaseError> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap_or_default()))
+    }
+    fn u32(&mut self) -> Result<u32, TablebaseError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap_or_default()))
+    }
+    fn u64(&mut self) -> Result<u64, TablebaseError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap_or_default()))
+    }
+}
+
+fn side(color: Color) -> usize {
+    match color {
+        Color::White => 0,
+        Color::Black => 1
+    }
+}
+
+fn push(events: &mut Vec<Vec<Event>>, distance: u16, event: Event) {
+    let distance = distance as usize;
+    if events.len() <= distance {
+        events.resize_with(distance + 1, Vec::new);
+    }
+    events[distance].push(event);
+}
+
+// Identifies the rules which affect the values of positions - the layout (its positions, adjacency and mills), flying,
+// protecting mills, double mills and re-forming mills - so that a tablebase isn't used for a game it wasn't generated for (FNV-1a).
+fn signature(rules: &Rules) -> u64 {
+    let layout = &rules.layout;
+    let mut words: Vec<u64> = vec![layout.len() as u64, rules.flying as u64, rules.protect_mills as u64, (rules.double_mill == DoubleMill::TwoCaptures) as u64, rules.reform_mills as u64];
+    words.extend(layout.names().iter().map(|name| name.bytes().fold(0, |word, byte| word << 8 | byte as u64)));
+    words.extend((0..layout.len()).map(|i| layout.adjacent(i) as u64));
+    words.extend(layout.mills().iter().map(|mill| *mill as u64));
+
+    words.iter().flat_map(|word| word.to_le_bytes()).fold(0xCBF2_9CE4_8422_2325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01B3))
+}
+
+// all masks with the given number of bits, taken from the bits of the given mask
+fn subsets(mask: u32, bits: u8) -> Vec<u32> {
+    let positions: Vec<u32> = (0..32).filter(|i| mask & 1 << i != 0).collect();
+    let length = positions.len();
+    if bits as usize > length {
+        return Vec::new();
+    }
+
+    // Gosper's hack - the next larger number with the same number of bits, over the positions of the mask
+    let mut subsets = Vec::new();
+    let mut combination: u64 = (1 << bits) - 1;
+    while combination < 1 << length {
+        subsets.push((0..length).filter(|i| combination & 1 << i != 0).fold(0, |result, i| result | 1 << positions[i]));
+        if combination == 0 {
+            break;
+        }
+        let lowest = combination & combination.wrapping_neg();
+        let ripple = combination + lowest;
+        combination = (((ripple ^ combination) >> 2) / lowest) | ripple;
+    }
+    subsets
+}
+
+// the rank of the mask among all masks with the same number of bits, in colexicographic order
+fn colex_rank(mask: u32) -> u64 {
+    (0..32).filter(|i| mask & 1 << i != 0).enumerate().map(|(k, i)| binomial(i, k + 1)).sum()
+}
+
+fn binomial(n: usize, k: usize) -> u64 {
+    static TABLE: OnceLock<Vec<Vec<u64>>> = OnceLock::new();
+
+    let table = TABLE.get_or_init(|| {
+        let mut table = vec![vec![0u64; 34]; 34];
+        for n in 0..34 {
+            table[n][0] = 1;
+            for k in 1..=n {
+                table[n][k] = table[n - 1][k - 1] + table[n - 1][k];
+            }
+        }
+        table
+    });
+    if k > n { 0 } else { table[n][k] }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::moves::Move;
+    use crate::enums::GameResult;
+    use crate::layout::Layout;
+
+    // the position in notation (see Game.to_notation()), with the given masks of white and black pieces in the moving phase
+    fn notation(layout: &Layout, white: u32, black: u32, current: Color) -> String {
+        let rows: Vec<String> = layout.rows().iter().map(|row| {
+            row.iter().map(|&i| if white & 1 << i != 0 { 'W' } else if black & 1 << i != 0 { 'B' } else { '1' }).collect()
+        }).collect();
+        format!("{} {} m 0 0", rows.join("/"), if current == Color::White { 'w' } else { 'b' })
+    }
+
+    // the value of the game for its current player, if it has ended
+    fn outcome(game: &Game) -> Option<Value> {
+        game.outcome().map(|result| match result {
+            GameResult::Win { winner, .. } if winner == game.current() => Value::Win(0),
+            GameResult::Win { .. } => Value::Loss(0),
+            GameResult::Draw { .. } => Value::Draw
+        })
+    }
+
+    // the value of the position after the move, for the player who made it
+    fn after(tablebase: &Tablebase, game: &mut Game, m: &Move) -> Value {
+        game.make_move(m).unwrap();
+        let value = match outcome(game).or_else(|| tablebase.probe(game)).unwrap() {
+            Value::Win(d) => Value::Loss(d),
+            Value::Loss(d) => Value::Win(d),
+            Value::Draw => Value::Draw
+        };
+        game.undo().unwrap();
+        value
+    }
+
+    // Checks every position of the tablebase against the moves generated by Game: a won position has a move to a lost one (and none quicker),
+    // a lost position only has moves to won ones (and at least one taking as long), and a drawn position has no move to a lost one.
+    fn check(tablebase: &Tablebase, rules: &Rules, white: u8, black: u8) {
+        let layout = &rules.layout;
+        let all = layout.all();
+        for white_mask in subsets(all, white) {
+            for black_mask in subsets(all & !white_mask, black) {
+                for color in [Color::White, Color::Black].iter() {
+                    let mut game = Game::from_notation(&notation(layout, white_mask, black_mask, *color), rules.clone()).unwrap();
+                    let value = tablebase.probe(&game).unwrap();
+                    if let Some(ended) = outcome(&game) {
+                        assert_eq!(value, ended);
+                        continue;
+                    }
+
+                    let after: Vec<Value> = game.legal_moves().iter().map(|m| after(tablebase, &mut game, m)).collect();
+                    let wins = after.iter().filter_map(|v| if let Value::Win(d) = v { Some(*d) } else { None });
+                    let losses = after.iter().filter_map(|v| if let Value::Loss(d) = v { Some(*d) } else { None });
+                    match value {
+                        Value::Win(d) => assert_eq!(wins.min(), Some(d - 1)),
+                        Value::Loss(d) => {
+                            assert!(wins.count() == 0 && !after.contains(&Value::Draw));
+                            assert_eq!(losses.max().map(|max| max + 1), Some(d));
+                        }
+                        Value::Draw => assert!(wins.count() == 0 && after.contains(&Value::Draw))
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_three_mens_morris() {
+        let rules = Rules::three_mens_morris();
+        let tablebase = Tablebase::generate(&rules, 3, 3);
+        assert!(tablebase.len(3, 3) < 84 * 20 / 4);
+        assert_eq!(tablebase.len(4, 3), 0);
+        check(&tablebase, &rules, 3, 3);
+
+        // white completes the mill on the top row
+        let game = Game::from_notation("WW1/BWB/B11 w m 0 0", rules.clone()).unwrap();
+        assert_eq!(tablebase.probe(&game), Some(Value::Win(1)));
+        assert_eq!(tablebase.probe(&Game::new(rules)), None);
+    }
+
+    #[test]
+    fn test_smaller_tables() {
+        // with 4 pieces, a capture leads to the table with 3 - which is solved first
+        let rules = Rules { pieces: 4, protect_mills: false, ..Rules::three_mens_morris() };
+        let tablebase = Tablebase::generate(&rules, 4, 3);
+        assert!(tablebase.len(3, 3) > 0 && tablebase.len(4, 3) > 0 && tablebase.len(3, 4) == 0);
+        check(&tablebase, &rules, 4, 3);
+
+        let rules = Rules { pieces: 4, flying: true, ..Rules::three_mens_morris() };
+        let tablebase = Tablebase::generate(&rules, 3, 4);
+        check(&tablebase, &rules, 3, 4);
+    }
+
+    #[test]
+    fn test_symmetric_positions() {
+        let rules = Rules::six_mens_morris();
+        let tablebase = Tablebase::generate(&rules, 3, 3);
+        let mut board = Board::with_layout(rules.layout.clone());
+        for position in ["a5", "c5", "e3"].iter() {
+            board.place_piece(Color::White, position).unwrap();
+        }
+        for position in ["b4", "c2", "d2"].iter() {
+            board.place_piece(Color::Black, position).unwrap();
+        }
+
+        // white completes the mill on the top row, and every symmetric version of the position has the same value
+        let value = tablebase.probe_position(board.occupied(Color::White), board.occupied(Color::Black), Color::White);
+        assert_eq!(value, Some(Value::Win(1)));
+        for transform in rules.layout.symmetries() {
+            let symmetric = board.transform(transform).unwrap();
+            assert_eq!(tablebase.probe_position(symmetric.occupied(Color::White), symmetric.occupied(Color::Black), Color::White), value);
+        }
+        assert_eq!(tablebase.probe_position(board.occupied(Color::White), board.occupied(Color::Black) | 1, Color::White), None);
+    }
+
+    #[test]
+    fn test_bytes() {
+        let rules = Rules::three_mens_morris();
+        let tablebase = Tablebase::generate(&rules, 3, 3);
+        let bytes = tablebase.to_bytes();
+        assert_eq!(bytes.len(), 8 + 8 + 4 + 2 + 4 + tablebase.len(3, 3) * (4 + 2 * 2));
+
+        let loaded = Tablebase::from_bytes(&bytes, &rules).unwrap();
+        let game = Game::from_notation("WW1/BWB/B11 b m 0 0", rules.clone()).unwrap();
+        assert_eq!(loaded.probe(&game), tablebase.probe(&game));
+        assert_eq!(loaded.to_bytes(), bytes);
+
+        assert_eq!(Tablebase::from_bytes(&bytes[1..], &rules).err(), Some(TablebaseError::InvalidFormat));
+        assert_eq!(Tablebase::from_bytes(&bytes[..bytes.len() - 1], &rules).err(), Some(TablebaseError::InvalidFormat));
+        assert_eq!(Tablebase::from_bytes(&bytes, &Rules::default()).err(), Some(TablebaseError::RulesMismatch));
+        assert_eq!(Tablebase::from_bytes(&bytes, &Rules { flying: true, ..Rules::three_mens_morris() }).err(), Some(TablebaseError::RulesMismatch));
+
+        // the number of pieces and the draw rules don't change the values of positions
+        assert!(Tablebase::from_bytes(&bytes, &Rules { pieces: 4, ..Rules::three_mens_morris() }).is_ok());
+    }
+
+    #[test]
+    fn test_reform_mills() {
+        // a game which can't re-form a mill right away isn't probed with a tablebase generated without that rule
+        let tablebase = Tablebase::generate(&Rules::three_mens_morris(), 3, 3);
+        let rules = Rules { reform_mills: false, ..Rules::three_mens_morris() };
+        let game = Game::from_notation("WW1/BWB/B11 b m 0 0", rules.clone()).unwrap();
+        assert!(tablebase.probe(&game).is_none());
+        assert_eq!(Tablebase::from_bytes(&tablebase.to_bytes(), &rules).err(), Some(TablebaseError::RulesMismatch));
+
+        // Morabaraba doesn't accept the tables of twelve men's morris, which has the same layout
+        let bytes = Tablebase::empty(&Rules::twelve_mens_morris()).to_bytes();
+        assert!(Tablebase::from_bytes(&bytes, &Rules::twelve_mens_morris()).is_ok());
+        assert_eq!(Tablebase::from_bytes(&bytes, &Rules::morabaraba()).err(), Some(TablebaseError::RulesMismatch));
+    }
+
+    #[test]
+    fn test_supports() {
+        // 7 against 6 pieces on nine men's morris still have ranks below 2^32, 7 against 7 don't
+        let rules = Rules::default();
+        assert!(Tablebase::supports(&rules, 7, 6));
+        assert!(!Tablebase::supports(&rules, 7, 7));
+        assert!(Tablebase::supports(&rules, 9, 3));
+        assert!(!Tablebase::supports(&Rules::three_mens_morris(), 3, 7));
+    }
+
+    #[test]
+    fn test_helpers() {
+        assert_eq!(subsets(0b1011, 2), [0b0011, 0b1001, 0b1010]);
+        assert_eq!(subsets(0b1011, 0), [0]);
+        assert_eq!(subsets(0b1011, 4), Vec::<u32>::new());
+        assert_eq!(subsets(u32::MAX, 1).len(), 32);
+
+        // the ranks of all masks with the same number of bits are 0, 1, 2, ... in some order
+        let mut ranks: Vec<u64> = subsets(0xFFFF, 3).into_iter().map(colex_rank).collect();
+        ranks.sort_unstable();
+        assert_eq!(ranks, (0..binomial(16, 3)).collect::<Vec<_>>());
+        assert_eq!(binomial(24, 3), 2024);
+        assert_eq!(binomial(3, 4), 0);
+
+        for value in [Value::Draw, Value::Win(1), Value::Win(99), Value::Loss(0), Value::Loss(42)].iter() {
+            assert_eq!(Value::decode(value.encode()), Some(*value));
+        }
+        assert_eq!(Value::decode(0xC000), None);
+        assert_eq!(Value::decode(1), None);
+    }
+}
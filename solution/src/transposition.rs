@@ -0,0 +1,216 @@
+use crate::moves::Move;
+use crate::ai::DECIDED;
+
+use std::mem;
+
+// the size of the table used by a search unless it's given another one, in megabytes
+pub const DEFAULT_SIZE: usize = 16;
+
+// the largest table that can be created, in megabytes - larger sizes are cut down to it
+pub const MAX_SIZE: usize = 1024;
+
+// what a stored score says about the position's true score - the search only finds bounds for positions outside its (alpha, beta) window
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Bound {
+    Exact,
+    Lower,  // the true score is at least the stored one (a move was good enough to cut the search off)
+    Upper   // the true score is at most the stored one (no move raised alpha)
+}
+
+// which entry is kept when a position is stored in a slot taken by another one
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Replacement {
+    Always,         // the new entry
+    DepthPreferred  // the new entry, unless the old one was searched deeper during the same search
+}
+
+// the result of searching a position
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Entry {
+    pub key: u64,           // the full hash of the position, as different positions share slots
+    pub depth: u8,          // the depth the position was searched to
+    pub bound: Bound,
+    pub score: i32,         // relative to the position (see Entry.score_at())
+    pub best: Option<Move>, // the best move found, searched first when the position is searched again
+    generation: u8          // the search which stored the entry
+}
+
+impl Entry {
+    // The score as seen from the given ply of the search. Wins and losses are stored as their distance from the position itself,
+    // as the same position can be reached at different plies.
+    pub fn score_at(&self, ply: i32) -> i32 {
+        if self.score > DECIDED {
+            self.score - ply
+        } else if self.score < -DECIDED {
+            self.score + ply
+        } else {
+            self.score
+        }
+    }
+}
+
+// A fixed-size table of searched positions, indexed by their Zobrist hashes (see Game.hash()), which lets the search reuse the results
+// for positions reached again by a different order of moves. As the hash doesn't include the history of the game, positions which
+// differ only in the draw rules (repetitions and moves without a mill) share entries - a small inaccuracy common to all such tables.
+pub struct TranspositionTable {
+    entries: Vec<Option<Entry>>,
+    replacement: Replacement,
+    generation: u8
+}
+
+impl TranspositionTable {
+    // Creates a table taking up to the given number of megabytes - the number of entries is rounded down to a power of two.
+    // A table of 0 megabytes stores nothing, which turns it off, and one of more than MAX_SIZE megabytes gets MAX_SIZE.
+    pub fn new(megabytes: usize) -> Self {
+        let count = megabytes.min(MAX_SIZE) * 1024 * 1024 / mem::size_of::<Option<Entry>>();
+        let count = if count == 0 { 0 } else { 1 << count.ilog2() };
+
+        Self { entries: vec![None; count], replacement: Replacement::DepthPreferred, generation: 0 }
+    }
+
+    // Returns the same table, with the given replacement scheme.
+    pub fn with_replacement(self, replacement: Replacement) -> Self {
+        Self { replacement, ..self }
+    }
+
+    // the number of entries the table can hold
+    pub fn capacity(&self) -> usize {
+        self.entries.len()
+    }
+
+    // the number of entries stored
+    pub fn len(&self) -> usize {
+        self.entries.iter().filter(|entry| entry.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // Removes all entries (e.g. when a new game starts).
+    pub fn clear(&mut self) {
+        self.entries.iter_mut().for_each(|entry| *entry = None);
+        self.generation = 0;
+    }
+
+    // Marks the start of a new search - the entries of earlier searches are still used, but replaced first.
+    pub fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    fn slot(&self, key: u64) -> usize {
+        key as usize & (self.entries.len() - 1)
+    }
+
+    // Returns the entry of the position with the given hash, if it's stored.
+    pub fn probe(&self, key: u64) -> Option<&Entry> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        self.entries[self.slot(key)].as_ref().filter(|entry| entry.key == key)
+    }
+
+    // Stores the result of searching the position with the given hash, unless the replacement scheme keeps the entry in its slot.
+    // The score is relative to the position (see Entry.score_at()).
+    pub fn store(&mut self, key: u64, depth: u8, bound: Bound, score: i32, best: Option<Move>) {
+        if self.entries.is_empty() {
+            return;
+        }
+
+        let slot = self.slot(key);
+        let replace = match (&self.entries[slot], self.replacement) {
+            (None, _) | (_, Replacement::Always) => true,
+            (Some(old), Replacement::DepthPreferred) => old.key == key || old.generation != self.generation || depth >= old.depth
+        };
+        if replace {
+            // a position searched again without finding a best move (all of its moves failed low) keeps the one found before
+            let best = best.or_else(|| self.entries[slot].filter(|old| old.key == key).and_then(|old| old.best));
+            self.entries[slot] = Some(Entry { key, depth, bound, score, best, generation: self.generation });
+        }
+    }
+
+    // Converts a score as seen from the given ply of the search to one relative to the position (the inverse of Entry.score_at()).
+    pub fn score_from(score: i32, ply: i32) -> i32 {
+        if score > DECIDED {
+            score + ply
+        } else if score < -DECIDED {
+            score - ply
+        } else {
+            score
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::WIN;
+
+    #[test]
+    fn test_store() {
+        let mut table = TranspositionTable::new(1);
+        assert!(table.capacity().is_power_of_two());
+        assert!(table.capacity() * mem::size_of::<Option<Entry>>() <= 1024 * 1024);
+        assert!(table.is_empty());
+
+        let m: Move = "a7".parse().unwrap();
+        table.store(42, 3, Bound::Exact, 100, Some(m));
+        let entry = table.probe(42).unwrap();
+        assert_eq!((entry.depth, entry.bound, entry.score, entry.best), (3, Bound::Exact, 100, Some(m)));
+
+        // another position in the same slot isn't mistaken for the stored one
+        let other = 42 + table.capacity() as u64;
+        assert!(table.probe(other).is_none());
+
+        // a shallower search of it doesn't replace the deeper entry - unless it's from a later search
+        table.store(other, 2, Bound::Lower, 50, None);
+        assert_eq!(table.probe(42).unwrap().score, 100);
+        table.new_search();
+        table.store(other, 2, Bound::Lower, 50, None);
+        assert_eq!(table.probe(other).unwrap().score, 50);
+        assert!(table.probe(42).is_none());
+
+        // storing the same position again keeps its best move, if no new one was found
+        table.store(other, 1, Bound::Upper, 20, None);
+        table.store(other, 1, Bound::Upper, 10, Some(m));
+        table.store(other, 1, Bound::Upper, 0, None);
+        assert_eq!(table.probe(other).unwrap().best, Some(m));
+        assert_eq!(table.len(), 1);
+
+        table.clear();
+        assert!(table.probe(other).is_none());
+    }
+
+    #[test]
+    fn test_replacement() {
+        let mut table = TranspositionTable::new(1).with_replacement(Replacement::Always);
+        let other = 42 + table.capacity() as u64;
+        table.store(42, 5, Bound::Exact, 100, None);
+        table.store(other, 1, Bound::Exact, 50, None);
+        assert!(table.probe(42).is_none());
+        assert_eq!(table.probe(other).unwrap().score, 50);
+
+        // a table of 0 megabytes stores nothing
+        let mut table = TranspositionTable::new(0);
+        table.store(42, 5, Bound::Exact, 100, None);
+        assert!(table.probe(42).is_none());
+        assert_eq!(table.capacity(), 0);
+    }
+
+    #[test]
+    fn test_scores() {
+        // a win 3 plies after the position, found at ply 5 of the search, is a win in 3 when the position is found at ply 1
+        let stored = TranspositionTable::score_from(WIN - 8, 5);
+        assert_eq!(stored, WIN - 3);
+
+        let mut table = TranspositionTable::new(1);
+        table.store(7, 1, Bound::Exact, stored, None);
+        assert_eq!(table.probe(7).unwrap().score_at(1), WIN - 4);
+
+        table.store(7, 1, Bound::Exact, TranspositionTable::score_from(-(WIN - 8), 5), None);
+        assert_eq!(table.probe(7).unwrap().score_at(1), -(WIN - 4));
+        table.store(7, 1, Bound::Exact, TranspositionTable::score_from(-120, 5), None);
+        assert_eq!(table.probe(7).unwrap().score_at(1), -120);
+    }
+}
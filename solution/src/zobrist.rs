@@ -0,0 +1,106 @@
+use crate::enums::{Color, Phase};
+use crate::rng::Rng;
+
+use std::sync::OnceLock;
+
+// Zobrist hashing - a position is hashed as the XOR of a random key for each of its parts (every piece on the board, the side to move,
+// the phase and the unplaced counts), so the hash can be updated with a single XOR whenever one of the parts changes.
+// The keys are generated from a fixed seed, so the hash of a position is the same in every run (and can be stored, e.g. in an opening book).
+struct Keys {
+    pieces: [[u64; 32]; 2],     // for each color, a key for a piece of that color at each index of the layout
+    black_to_move: u64,
+    moving: u64,
+    unplaced: [[u64; 256]; 2],  // for each color, a key for each possible number of unplaced pieces
+    last_move: [[[u64; 32]; 2]; 2] // for each color, a key for each index their last move left, and for each index it went to
+}
+
+const SEED: u64 = 0x4D4F_5252_4953; // "MORRIS"
+
+fn keys() -> &'static Keys {
+    static KEYS: OnceLock<Keys> = OnceLock::new();
+
+    KEYS.get_or_init(|| {
+        let mut rng = Rng::new(SEED);
+        let mut keys = Keys { pieces: [[0; 32]; 2], black_to_move: 0, moving: 0, unplaced: [[0; 256]; 2], last_move: [[[0; 32]; 2]; 2] };
+
+        for key in keys.pieces.iter_mut().flatten() {
+            *key = rng.next_u64();
+        }
+        keys.black_to_move = rng.next_u64();
+        keys.moving = rng.next_u64();
+        for key in keys.unplaced.iter_mut().flatten() {
+            *key = rng.next_u64();
+        }
+        for key in keys.last_move.iter_mut().flatten().flatten() {
+            *key = rng.next_u64();
+        }
+        keys
+    })
+}
+
+fn color_index(color: Color) -> usize {
+    match color {
+        Color::White => 0,
+        Color::Black => 1
+    }
+}
+
+// the key of a piece of the given color at the given index of the layout
+pub fn piece(color: Color, index: usize) -> u64 {
+    keys().pieces[color_index(color)][index]
+}
+
+// the key of the side to move (0 for white, so only black to move changes the hash)
+pub fn side(color: Color) -> u64 {
+    match color {
+        Color::White => 0,
+        Color::Black => keys().black_to_move
+    }
+}
+
+// the key of the phase (0 for placing)
+pub fn phase(phase: Phase) -> u64 {
+    match phase {
+        Phase::Placing => 0,
+        Phase::Moving => keys().moving
+    }
+}
+
+// the key of the given number of unplaced pieces of a color
+pub fn unplaced(color: Color, count: u8) -> u64 {
+    keys().unplaced[color_index(color)][count as usize]
+}
+
+// The key of the given player's last move, from one index to another. It isn't part of Game.hash(), but it's added to it by a search
+// when the rules don't allow re-forming mills right away, which makes the last moves matter (see AlphaBeta.key()).
+pub fn last_move(color: Color, from: usize, to: usize) -> u64 {
+    let keys = &keys().last_move[color_index(color)];
+    keys[0][from] ^ keys[1][to]
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys() {
+        // the keys are fixed, so hashes can be stored and compared between runs
+        let mut rng = Rng::new(SEED);
+        assert_eq!(piece(Color::White, 0), rng.next_u64());
+        assert_eq!(piece(Color::White, 1), rng.next_u64());
+
+        let mut all: Vec<u64> = (0..32).flat_map(|index| vec![piece(Color::White, index), piece(Color::Black, index)]).collect();
+        all.extend((0..=255).flat_map(|count| vec![unplaced(Color::White, count), unplaced(Color::Black, count)]));
+        all.extend((0..32).flat_map(|index| vec![last_move(Color::White, index, 0), last_move(Color::Black, 0, index)]));
+        all.push(side(Color::Black));
+        all.push(phase(Phase::Moving));
+        let count = all.len();
+        all.sort_unstable();
+        all.dedup();
+        assert_eq!(all.len(), count);
+
+        assert_eq!(side(Color::White), 0);
+        assert_eq!(phase(Phase::Placing), 0);
+    }
+}